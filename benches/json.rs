@@ -0,0 +1,52 @@
+//! Compares parsing a `latest` response with `serde_json` and `simd-json`.
+//!
+//! Run with `cargo bench --features simd-json --bench json`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use currencyapi_rs::models::LatestResponse;
+
+const ITERATIONS: u32 = 2_000;
+
+/// Builds a body similar to a `latest` response with all supported currencies.
+fn sample_body() -> String {
+    let rates: Vec<String> = (0..180)
+        .map(|idx| {
+            let code = format!("C{:02}", idx);
+            format!(
+                r#""{code}":{{"code":"{code}","value":{}}}"#,
+                1.0 + idx as f64 / 7.0
+            )
+        })
+        .collect();
+    format!(
+        r#"{{"meta":{{"last_updated_at":"2024-01-01T23:59:59Z"}},"data":{{{}}}}}"#,
+        rates.join(",")
+    )
+}
+
+fn measure(name: &str, mut parse: impl FnMut() -> LatestResponse) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(parse());
+    }
+    let per_iteration: Duration = start.elapsed() / ITERATIONS;
+    println!("{name:<12} {per_iteration:?} per response");
+}
+
+fn main() {
+    let body = sample_body();
+    let expected: LatestResponse = serde_json::from_str(&body).unwrap();
+    let mut bytes = body.as_bytes().to_vec();
+    assert_eq!(
+        simd_json::serde::from_slice::<LatestResponse>(&mut bytes).unwrap(),
+        expected
+    );
+
+    measure("serde_json", || serde_json::from_str(black_box(&body)).unwrap());
+    measure("simd-json", || {
+        let mut bytes = black_box(&body).as_bytes().to_vec();
+        simd_json::serde::from_slice(&mut bytes).unwrap()
+    });
+}
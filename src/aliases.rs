@@ -0,0 +1,112 @@
+//! Module that contains the [CurrencyAliases] table for outdated currency codes
+
+use std::collections::HashMap;
+
+/// Built-in mapping of outdated currency codes to the code that replaced them
+const DEFAULT_ALIASES: &[(&str, &str)] = &[
+    ("BYR", "BYN"),
+    ("EEK", "EUR"),
+    ("GHC", "GHS"),
+    ("HRK", "EUR"),
+    ("LTL", "EUR"),
+    ("LVL", "EUR"),
+    ("MRO", "MRU"),
+    ("ROL", "RON"),
+    ("SLL", "SLE"),
+    ("STD", "STN"),
+    ("TRL", "TRY"),
+    ("VEF", "VES"),
+    ("ZMK", "ZMW"),
+];
+
+/// Table of deprecated currency codes and their current replacement.
+///
+/// The [Default] table contains common redenominations and euro adoptions.
+/// Use [CurrencyAliases::insert] to extend or override it or start from
+/// [CurrencyAliases::empty] to provide a table of your own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrencyAliases {
+    table: HashMap<String, String>,
+}
+
+impl Default for CurrencyAliases {
+    fn default() -> Self {
+        let table = DEFAULT_ALIASES
+            .iter()
+            .map(|(alias, canonical)| (String::from(*alias), String::from(*canonical)))
+            .collect();
+        Self { table }
+    }
+}
+
+impl CurrencyAliases {
+    /// Creates a table without any aliases.
+    pub fn empty() -> Self {
+        Self {
+            table: HashMap::new(),
+        }
+    }
+
+    /// Adds or replaces the mapping of a deprecated `alias` to its `canonical` code.
+    pub fn insert(mut self, alias: &str, canonical: &str) -> Self {
+        self.table
+            .insert(alias.to_uppercase(), canonical.to_uppercase());
+        self
+    }
+
+    /// Returns the current code for `code` and whether `code` is deprecated.
+    ///
+    /// Codes without an alias are returned unchanged.
+    pub fn normalize_alias(&self, code: &str) -> (String, bool) {
+        match self.table.get(&code.to_uppercase()) {
+            Some(canonical) => (canonical.clone(), true),
+            None => (String::from(code), false),
+        }
+    }
+
+    /// Normalizes every code of a comma separated currency list.
+    pub(crate) fn normalize_list(&self, currencies: &str) -> String {
+        currencies
+            .split(',')
+            .map(|code| self.normalize_alias(code.trim()).0)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[cfg(test)]
+mod aliases_test {
+    use super::*;
+
+    #[test]
+    fn should_map_known_alias_to_canonical_code() {
+        let aliases = CurrencyAliases::default();
+        assert_eq!(aliases.normalize_alias("HRK"), (String::from("EUR"), true));
+        assert_eq!(aliases.normalize_alias("vef"), (String::from("VES"), true));
+    }
+
+    #[test]
+    fn should_pass_through_non_aliased_code() {
+        let aliases = CurrencyAliases::default();
+        assert_eq!(aliases.normalize_alias("USD"), (String::from("USD"), false));
+    }
+
+    #[test]
+    fn should_allow_overriding_the_table() {
+        let aliases = CurrencyAliases::default()
+            .insert("HRK", "USD")
+            .insert("XYZ", "ABC");
+        assert_eq!(aliases.normalize_alias("HRK"), (String::from("USD"), true));
+        assert_eq!(aliases.normalize_alias("XYZ"), (String::from("ABC"), true));
+        assert_eq!(
+            CurrencyAliases::empty().normalize_alias("HRK"),
+            (String::from("HRK"), false)
+        );
+    }
+
+    #[test]
+    fn should_normalize_currency_list() {
+        let aliases = CurrencyAliases::default();
+        assert_eq!(aliases.normalize_list("USD,HRK,LTL"), "USD,EUR,EUR");
+    }
+}
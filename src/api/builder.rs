@@ -0,0 +1,413 @@
+//! Module that contains the [CurrencyapiBuilder] used to configure a [Currencyapi] client
+
+use crate::api::{
+    Currencyapi, ErrorHook, ErrorHookFn, NonFiniteRates, NumberHandling, Recording, Settings,
+};
+use crate::cache::ResponseCache;
+use crate::error::CurrencyapiError;
+use crate::latency::{LatencyWindow, LATENCY_WINDOW};
+use crate::limiter::{LeakyBucket, SystemClock};
+use crate::{CurrencyAliases, RetryConfig};
+use crate::utils;
+#[cfg(feature = "chrono")]
+use chrono::NaiveDate;
+use reqwest::header::HeaderValue;
+use reqwest::Client;
+use std::collections::{BTreeSet, HashMap};
+use std::num::NonZeroUsize;
+#[cfg(feature = "chrono")]
+use std::sync::atomic::AtomicI64;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// Number of requests a client sends concurrently unless configured otherwise
+const DEFAULT_MAX_CONCURRENCY: usize = 32;
+
+/// Builder for a [Currencyapi] client with non-default options.
+/// Create a new builder with [Currencyapi::builder].
+#[derive(Debug, Clone)]
+pub struct CurrencyapiBuilder {
+    api_key: String,
+    base_url: Option<String>,
+    api_version: String,
+    track_seen_codes: bool,
+    number_handling: NumberHandling,
+    non_finite_rates: NonFiniteRates,
+    accept: String,
+    aliases: Option<CurrencyAliases>,
+    cache_ttl: Option<Duration>,
+    cache_capacity: Option<NonZeroUsize>,
+    retry: Option<RetryConfig>,
+    value_precision: Option<usize>,
+    max_convert_value: Option<f64>,
+    on_error: Option<ErrorHookFn>,
+    reject_duplicate_currencies: bool,
+    strict_base_currency: bool,
+    leaky_bucket: Option<(f64, u32)>,
+    base_fallback: Option<String>,
+    hedge_after: Option<Duration>,
+    timeout: Option<Duration>,
+    clock_skew_tolerance: Duration,
+    server_clock: bool,
+    #[cfg(feature = "chrono")]
+    history_start: NaiveDate,
+    max_concurrency: NonZeroUsize,
+    recording: Option<Recording>,
+    http_client: Option<Client>,
+    user_agent: Option<String>,
+}
+
+impl CurrencyapiBuilder {
+    pub(crate) fn new(api_key: &str) -> Self {
+        Self {
+            api_key: String::from(api_key),
+            base_url: None,
+            api_version: String::from(utils::baseline::DEFAULT_API_VERSION),
+            track_seen_codes: false,
+            number_handling: NumberHandling::default(),
+            non_finite_rates: NonFiniteRates::default(),
+            accept: String::from("application/json"),
+            aliases: None,
+            cache_ttl: None,
+            cache_capacity: None,
+            retry: None,
+            value_precision: None,
+            max_convert_value: None,
+            on_error: None,
+            reject_duplicate_currencies: false,
+            strict_base_currency: false,
+            leaky_bucket: None,
+            base_fallback: None,
+            hedge_after: None,
+            timeout: None,
+            clock_skew_tolerance: Duration::ZERO,
+            server_clock: false,
+            #[cfg(feature = "chrono")]
+            history_start: super::FIRST_HISTORICAL_DATE,
+            max_concurrency: NonZeroUsize::new(DEFAULT_MAX_CONCURRENCY).unwrap(),
+            recording: None,
+            http_client: None,
+            user_agent: None,
+        }
+    }
+
+    /// Sends all requests to `base_url` instead of the currencyapi, e.g. a mock server in
+    /// integration tests or a regional proxy. The endpoint paths are appended to the path
+    /// of `base_url`. Defaults to `https://api.currencyapi.com/v3/`.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(String::from(base_url));
+        self
+    }
+
+    /// Sends requests to the api version `version`, e.g. `v4` for
+    /// `https://api.currencyapi.com/v4/`. Defaults to `v3`.
+    /// Has no effect if a [CurrencyapiBuilder::base_url] is set, whose path already
+    /// contains the version.
+    pub fn api_version(mut self, version: &str) -> Self {
+        self.api_version = String::from(version);
+        self
+    }
+
+    /// Records every currency code contained in a response so they can be
+    /// retrieved later with [Currencyapi::seen_codes]. Disabled by default.
+    pub fn track_seen_codes(mut self, enabled: bool) -> Self {
+        self.track_seen_codes = enabled;
+        self
+    }
+
+    /// Sets how numbers in responses are handled, see [NumberHandling].
+    /// Defaults to [NumberHandling::Lossy].
+    pub fn number_handling(mut self, number_handling: NumberHandling) -> Self {
+        self.number_handling = number_handling;
+        self
+    }
+
+    /// Sets how rates that are NaN or infinite are handled, see [NonFiniteRates].
+    /// Defaults to [NonFiniteRates::Skip], so a single invalid rate does not affect
+    /// the other rates of a table.
+    pub fn non_finite_rates(mut self, non_finite_rates: NonFiniteRates) -> Self {
+        self.non_finite_rates = non_finite_rates;
+        self
+    }
+
+    /// Sets the `Accept` header sent with each request, e.g. to pin a schema version
+    /// like `application/vnd.currencyapi.v3+json`. Defaults to `application/json`.
+    pub fn accept(mut self, accept: &str) -> Self {
+        self.accept = String::from(accept);
+        self
+    }
+
+    /// Replaces deprecated currency codes in the `base_currency` and `currencies`
+    /// parameters with their current code from `aliases` before sending a request.
+    /// Disabled by default.
+    pub fn currency_aliases(mut self, aliases: CurrencyAliases) -> Self {
+        self.aliases = Some(aliases);
+        self
+    }
+
+    /// Caches successful responses in memory for `ttl` so repeated identical requests
+    /// are answered without calling the api. Disabled by default.
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Limits the response cache to at most `capacity` entries, evicting the least
+    /// recently used entry when full. Only applies if [CurrencyapiBuilder::cache_ttl]
+    /// is set, the cache is unbounded by default.
+    pub fn cache_capacity(mut self, capacity: NonZeroUsize) -> Self {
+        self.cache_capacity = Some(capacity);
+        self
+    }
+
+    /// Retries requests failing with transient connection errors, timeouts or status `429`
+    /// according to `config`, honoring the `Retry-After` header of rate limited requests.
+    /// Disabled by default.
+    pub fn retry(mut self, config: RetryConfig) -> Self {
+        self.retry = Some(config);
+        self
+    }
+
+    /// Formats the `value` parameter of convert requests with `precision` decimal places.
+    /// Defaults to the shortest representation that keeps the full precision.
+    pub fn value_precision(mut self, precision: usize) -> Self {
+        self.value_precision = Some(precision);
+        self
+    }
+
+    /// Rejects convert requests whose absolute value exceeds `max` with
+    /// [CurrencyapiError::ValueOutOfRange] before sending them, e.g. to catch a misplaced
+    /// decimal separator. There is no limit by default.
+    pub fn max_convert_value(mut self, max: f64) -> Self {
+        self.max_convert_value = Some(max);
+        self
+    }
+
+    /// Registers a callback that is invoked with the error of every failed request
+    /// before it is returned, e.g. to report errors centrally.
+    pub fn on_error(mut self, hook: ErrorHook) -> Self {
+        self.on_error = Some(ErrorHookFn(hook));
+        self
+    }
+
+    /// Rejects responses that contain the same currency code more than once with
+    /// [CurrencyapiError::DuplicateCurrency] instead of silently keeping the last entry.
+    /// Disabled by default.
+    pub fn reject_duplicate_currencies(mut self, enabled: bool) -> Self {
+        self.reject_duplicate_currencies = enabled;
+        self
+    }
+
+    /// Rejects responses of all rate-returning methods whose base currency differs from the
+    /// requested one with [CurrencyapiError::UnexpectedBaseCurrency], e.g. if the api
+    /// silently applied its default base because the parameter was dropped. The base of a
+    /// response is taken from its meta if present and otherwise from the currency with a
+    /// rate of 1.0, or with the converted value equal to the amount for
+    /// [Currencyapi::convert](super::Currencyapi::convert), so responses not containing
+    /// the requested base can only be checked by their meta. Disabled by default.
+    pub fn strict_base_currency(mut self, enabled: bool) -> Self {
+        self.strict_base_currency = enabled;
+        self
+    }
+
+    /// Limits outgoing requests with a leaky bucket that lets `burst` requests pass at
+    /// once and then drains at `rate` requests per second. Requests exceeding the burst
+    /// wait until there is room again. The limiter is shared by all clones of the client.
+    /// Disabled by default.
+    pub fn leaky_bucket(mut self, rate: f64, burst: u32) -> Self {
+        self.leaky_bucket = Some((rate, burst));
+        self
+    }
+
+    /// Requests rates with `base` as base currency if the requested base currency is
+    /// not supported, and rebases them locally to the requested one. The rebased rates
+    /// carry the fallback in [Meta::fallback_base](crate::models::Meta::fallback_base).
+    /// Only applies to rate tables of the typed helpers and requires the requested base
+    /// currency to be quoted against `base`. Disabled by default.
+    pub fn base_fallback(mut self, base: &str) -> Self {
+        self.base_fallback = Some(String::from(base));
+        self
+    }
+
+    /// Sends a second identical request if the first one has not completed after `delay`
+    /// and uses whichever response arrives first, cancelling the other. This lowers tail
+    /// latency at the cost of extra quota for slow requests. Only the idempotent GET
+    /// requests of the typed methods are hedged, raw responses are never duplicated.
+    /// Disabled by default.
+    pub fn hedge_after(mut self, delay: Duration) -> Self {
+        self.hedge_after = Some(delay);
+        self
+    }
+
+    /// Aborts requests that have not completed within `timeout`, from connecting until the
+    /// response body has been read, with [CurrencyapiError::Timeout]. Requests never
+    /// time out by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Treats dates up to `tolerance` before the current day as today when choosing
+    /// between latest and historical rates, e.g. so a clock running slightly ahead does
+    /// not request historical rates of a day the api has not closed yet. Only affects
+    /// the methods of the `chrono` feature. Defaults to zero.
+    pub fn clock_skew_tolerance(mut self, tolerance: Duration) -> Self {
+        self.clock_skew_tolerance = tolerance;
+        self
+    }
+
+    /// Determines the current day from the `Date` header of the latest api response
+    /// instead of the local clock, falling back to the local clock until the first
+    /// response arrives. Only affects the methods of the `chrono` feature. Disabled by
+    /// default.
+    pub fn server_clock(mut self, enabled: bool) -> Self {
+        self.server_clock = enabled;
+        self
+    }
+
+    /// Sets the first day with historical rates available to the api key, e.g. because the
+    /// plan only includes a limited history. [Currencyapi::trailing] and
+    /// [Currencyapi::top_movers] never request rates before this day. Only affects the
+    /// methods of the `chrono` feature. Defaults to 1999-01-01, the first day with
+    /// historical rates of the api.
+    #[cfg(feature = "chrono")]
+    pub fn history_start(mut self, date: NaiveDate) -> Self {
+        self.history_start = date;
+        self
+    }
+
+    /// Limits the number of requests in flight at once to `limit` across all methods,
+    /// including the concurrent requests of batch methods. Requests exceeding the limit
+    /// wait until another request completes. The limit is shared by all clones of the
+    /// client. Defaults to 32.
+    pub fn max_concurrency(mut self, limit: NonZeroUsize) -> Self {
+        self.max_concurrency = limit;
+        self
+    }
+
+    /// Appends every request sent to the api and the raw response received for it to
+    /// `recording`, e.g. to replay the traffic through a mock in deterministic tests.
+    /// The value of the `apikey` header is redacted. Responses answered from the cache and the raw responses
+    /// of the `*_stream_response` methods are not recorded. Disabled by default.
+    pub fn record_to(mut self, recording: Recording) -> Self {
+        self.recording = Some(recording);
+        self
+    }
+
+    /// Sends all requests with `client` instead of a client constructed for the api, e.g. to
+    /// share the connection pool, DNS cache and TLS configuration of a service.
+    ///
+    /// The `apikey` and `Content-Type` headers and the [CurrencyapiBuilder::timeout] are
+    /// added to each request instead. The user agent, proxies and all other options of
+    /// `client` are the responsibility of the caller.
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Sends `user_agent` as `User-Agent` header instead of `currencyapi-rs/<version>`.
+    /// Has no effect if a [CurrencyapiBuilder::http_client] is provided.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(String::from(user_agent));
+        self
+    }
+
+    /// Creates the configured [Currencyapi] client.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the http client cannot be constructed,
+    /// if the [CurrencyapiBuilder::base_url] is not a valid http url, if the
+    /// [CurrencyapiBuilder::api_version] is empty or contains a `/`,
+    /// if the `Accept` header is not a valid header value, if the rate of the
+    /// [CurrencyapiBuilder::leaky_bucket] is not a positive number or if the
+    /// [CurrencyapiBuilder::max_convert_value] is negative or not a number.
+    pub fn build(self) -> Result<Currencyapi, CurrencyapiError> {
+        let accept = HeaderValue::from_str(&self.accept)?;
+        if let Some(max) = self
+            .max_convert_value
+            .filter(|max| max.is_nan() || *max < 0.0)
+        {
+            return Err(CurrencyapiError::InvalidConfiguration {
+                reason: format!("max convert value must not be negative, got {}", max),
+            });
+        }
+        let (base_url, api_version) = match self.base_url.as_deref() {
+            Some(base_url) => (utils::baseline::parse_base_url(base_url)?, None),
+            None => (
+                utils::baseline::versioned_base_url(&self.api_version)?,
+                Some(self.api_version),
+            ),
+        };
+        let (request_headers, user_agent) = match self.http_client {
+            Some(_) => (Some(utils::baseline::default_headers(&self.api_key)?), None),
+            None => {
+                let user_agent = self.user_agent.as_deref();
+                let user_agent = user_agent.unwrap_or(utils::baseline::DEFAULT_USER_AGENT);
+                (None, Some(String::from(user_agent)))
+            }
+        };
+        let settings = Arc::new(Settings {
+            api_key: self.api_key,
+            base_url,
+            api_version,
+            number_handling: self.number_handling,
+            non_finite_rates: self.non_finite_rates,
+            accept,
+            aliases: self.aliases,
+            retry: self.retry,
+            value_precision: self.value_precision,
+            max_convert_value: self.max_convert_value,
+            on_error: self.on_error,
+            reject_duplicate_currencies: self.reject_duplicate_currencies,
+            strict_base_currency: self.strict_base_currency,
+            base_fallback: self.base_fallback,
+            hedge_after: self.hedge_after,
+            timeout: self.timeout,
+            clock_skew_tolerance: self.clock_skew_tolerance,
+            server_clock: self.server_clock,
+            #[cfg(feature = "chrono")]
+            history_start: self.history_start,
+            max_concurrency: self.max_concurrency,
+            recording: self.recording,
+            request_headers,
+            user_agent,
+        });
+        let client = match self.http_client {
+            Some(client) => client,
+            None => utils::baseline::construct_client(settings.user_agent.as_deref(), &settings)?,
+        };
+        let seen_codes = self
+            .track_seen_codes
+            .then(|| Arc::new(Mutex::new(BTreeSet::new())));
+        let cache = self
+            .cache_ttl
+            .map(|ttl| Arc::new(ResponseCache::new(ttl, self.cache_capacity)));
+        let limiter = self
+            .leaky_bucket
+            .map(|(rate, burst)| {
+                LeakyBucket::new(rate, burst, Arc::new(SystemClock)).ok_or_else(|| {
+                    CurrencyapiError::InvalidConfiguration {
+                        reason: format!("leaky bucket rate must be positive, got {}", rate),
+                    }
+                })
+            })
+            .transpose()?
+            .map(Arc::new);
+        Ok(Currencyapi {
+            client,
+            settings,
+            seen_codes,
+            cache,
+            limiter,
+            latencies: Arc::new(LatencyWindow::new(LATENCY_WINDOW)),
+            permits: Arc::new(Semaphore::new(self.max_concurrency.get())),
+            rate_overrides: Arc::new(Mutex::new(HashMap::new())),
+            quote_scales: Arc::new(Mutex::new(HashMap::new())),
+            quota: Arc::new(Mutex::new(None)),
+            #[cfg(feature = "chrono")]
+            server_offset: Arc::new(AtomicI64::new(0)),
+        })
+    }
+}
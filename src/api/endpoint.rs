@@ -0,0 +1,73 @@
+//! Module that contains the [Endpoint]s of the currencyapi
+
+use crate::error::CurrencyapiError;
+use crate::utils::baseline::construct_base_url;
+use reqwest::Url;
+
+/// Endpoint of the currencyapi
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
+pub enum Endpoint {
+    /// Quota status of the api key
+    Status,
+    /// Metadata of all supported currencies
+    Currencies,
+    /// Latest exchange rates
+    Latest,
+    /// Exchange rates of a past date
+    Historical,
+    /// Conversion of a value into other currencies
+    Convert,
+    /// Exchange rates of a time range
+    Range,
+}
+
+impl Endpoint {
+    /// Returns the path of the endpoint relative to the base url.
+    pub fn path(self) -> &'static str {
+        match self {
+            Endpoint::Status => "status",
+            Endpoint::Currencies => "currencies",
+            Endpoint::Latest => "latest",
+            Endpoint::Historical => "historical",
+            Endpoint::Convert => "convert",
+            Endpoint::Range => "range",
+        }
+    }
+
+    /// Constructs the url of the endpoint relative to `base_url` without query parameters.
+    pub(crate) fn url(self, base_url: &Url) -> Result<Url, CurrencyapiError> {
+        construct_base_url(base_url, Some(self.path()))
+    }
+}
+
+#[cfg(test)]
+mod endpoint_test {
+    use super::Endpoint;
+    use crate::utils::baseline::{versioned_base_url, DEFAULT_API_VERSION};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn should_map_each_endpoint_to_its_path() {
+        let expected = [
+            (Endpoint::Status, "status"),
+            (Endpoint::Currencies, "currencies"),
+            (Endpoint::Latest, "latest"),
+            (Endpoint::Historical, "historical"),
+            (Endpoint::Convert, "convert"),
+            (Endpoint::Range, "range"),
+        ];
+        assert_eq!(Endpoint::iter().count(), expected.len());
+        for (endpoint, path) in expected {
+            assert_eq!(endpoint.path(), path);
+        }
+    }
+
+    #[test]
+    fn should_append_path_to_base_url() {
+        let url = Endpoint::Historical
+            .url(&versioned_base_url(DEFAULT_API_VERSION).unwrap())
+            .unwrap();
+        assert_eq!(url.path(), "/v3/historical");
+        assert_eq!(url.query(), None);
+    }
+}
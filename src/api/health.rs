@@ -0,0 +1,43 @@
+//! Module that contains the [Health] returned by [Currencyapi::health]
+
+#[cfg(doc)]
+use crate::api::Currencyapi;
+use crate::models::StatusResponse;
+use reqwest::StatusCode;
+
+/// Reachability, authorization and remaining quota of the api, e.g. for the liveness or
+/// readiness endpoint of a service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Health {
+    /// `true` if the api answered the status request
+    pub reachable: bool,
+    /// `true` if the api key was accepted, i.e. the status request succeeded or only
+    /// failed because the quota is exhausted
+    pub authorized: bool,
+    /// Remaining requests of the current month or `None` if the api did not report them
+    pub quota_remaining: Option<u64>,
+}
+
+impl Health {
+    /// Health of an api that could not be reached.
+    pub(crate) fn unreachable() -> Self {
+        Self::default()
+    }
+
+    /// Derives the health from the status code and body of a status response.
+    pub(crate) fn from_response(status: StatusCode, body: &str) -> Self {
+        let quota_exhausted = status == StatusCode::TOO_MANY_REQUESTS;
+        let quota_remaining = if status.is_success() {
+            serde_json::from_str::<StatusResponse>(body)
+                .ok()
+                .map(|response| response.quotas.month.remaining)
+        } else {
+            quota_exhausted.then_some(0)
+        };
+        Self {
+            reachable: true,
+            authorized: status.is_success() || quota_exhausted,
+            quota_remaining,
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Bundled list of known currency codes for client-side validation
+
+use crate::error::CurrencyapiError;
+use reqwest::Url;
+
+/// ISO 4217 codes including precious metals, codes of withdrawn currencies and dependencies
+/// still returned by the api and common crypto currencies, sorted for binary search
+const KNOWN_CODES: &[&str] = &[
+    "ADA", "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARB", "ARS", "ATOM", "AUD", "AVAX", "AWG",
+    "AZN", "BAM", "BBD", "BCH", "BDT", "BGN", "BHD", "BIF", "BMD", "BNB", "BND", "BOB", "BOV",
+    "BRL", "BSD", "BTC", "BTN", "BUSD", "BWP", "BYN", "BYR", "BZD", "CAD", "CDF", "CHE", "CHF",
+    "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP", "CVE", "CZK", "DAI", "DJF",
+    "DKK", "DOGE", "DOP", "DOT", "DZD", "EGP", "ERN", "ETB", "ETH", "EUR", "FJD", "FKP", "GBP",
+    "GEL", "GGP", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR",
+    "ILS", "IMP", "INR", "IQD", "IRR", "ISK", "JEP", "JMD", "JOD", "JPY", "KES", "KGS", "KHR",
+    "KMF", "KPW", "KRW", "KWD", "KYD", "KZT", "LAK", "LBP", "LINK", "LKR", "LRD", "LSL", "LTC",
+    "LTL", "LVL", "LYD", "MAD", "MATIC", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRO", "MRU",
+    "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN", "NIO", "NOK", "NPR", "NZD",
+    "OMR", "OP", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD", "RUB",
+    "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHIB", "SHP", "SLE", "SLL", "SOL", "SOS",
+    "SRD", "SSP", "STD", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TON", "TOP",
+    "TRX", "TRY", "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "USDC", "USDT", "USN", "UYI", "UYU",
+    "UYW", "UZS", "VED", "VEF", "VES", "VND", "VUV", "WST", "XAF", "XAG", "XAU", "XBA", "XBB",
+    "XBC", "XBD", "XCD", "XCG", "XDR", "XLM", "XOF", "XPD", "XPF", "XPT", "XRP", "XSU", "XTS",
+    "XUA", "XXX", "YER", "ZAR", "ZMK", "ZMW", "ZWG", "ZWL",
+];
+
+/// Returns `true` if `code` is contained in the bundled list, ignoring ASCII case.
+pub(crate) fn is_known(code: &str) -> bool {
+    KNOWN_CODES
+        .binary_search(&code.to_ascii_uppercase().as_str())
+        .is_ok()
+}
+
+/// Checks the `base_currency` and each entry of the `currencies` query parameter of `url`
+/// against the bundled list before the request is sent.
+pub(crate) fn validate_url(url: &Url) -> Result<(), CurrencyapiError> {
+    for (key, value) in url.query_pairs() {
+        let codes: Vec<&str> = match key.as_ref() {
+            "base_currency" => vec![value.as_ref()],
+            "currencies" => value.split(',').filter(|code| !code.is_empty()).collect(),
+            _ => continue,
+        };
+        if let Some(code) = codes.into_iter().find(|code| !is_known(code)) {
+            return Err(CurrencyapiError::UnknownCurrency {
+                code: String::from(code),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod known_codes_test {
+    use super::*;
+
+    #[test]
+    fn should_keep_codes_sorted_and_unique() {
+        assert!(KNOWN_CODES.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn should_accept_known_codes_and_reject_typos() {
+        assert!(is_known("USD"));
+        assert!(is_known("btc"));
+        assert!(!is_known("USDD"));
+        let url =
+            Url::parse("https://example.com/latest?base_currency=USD&currencies=EUR,GBP").unwrap();
+        assert!(validate_url(&url).is_ok());
+        let url =
+            Url::parse("https://example.com/latest?base_currency=USD&currencies=EUR,USDD").unwrap();
+        assert!(matches!(
+            validate_url(&url),
+            Err(CurrencyapiError::UnknownCurrency { code }) if code == "USDD"
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+//! Module that contains the [LatestRequestBuilder] returned by [Currencyapi::latest_builder]
+
+use crate::api::{append_currencies, append_currency_type, join_codes, Currencyapi, Endpoint};
+use crate::error::CurrencyapiError;
+use crate::models;
+use reqwest::Url;
+
+/// Builder for a `latest` request with optional parameters.
+/// Create a new builder with [Currencyapi::latest_builder].
+///
+/// Only the parameters that were set are added to the request, all others are left
+/// to the defaults of the api.
+#[derive(Debug, Clone)]
+pub struct LatestRequestBuilder<'c> {
+    api: &'c Currencyapi,
+    base_currency: Option<String>,
+    currencies: String,
+    currency_type: Option<models::CurrencyType>,
+}
+
+impl<'c> LatestRequestBuilder<'c> {
+    pub(crate) fn new(api: &'c Currencyapi) -> Self {
+        Self {
+            api,
+            base_currency: None,
+            currencies: String::new(),
+            currency_type: None,
+        }
+    }
+
+    /// Sets the base currency the rates are quoted against. The api uses `USD` if unset.
+    pub fn base_currency(mut self, base_currency: &str) -> Self {
+        self.base_currency = Some(String::from(base_currency));
+        self
+    }
+
+    /// Restricts the rates to the given currency codes. All currencies are returned if unset.
+    pub fn currencies(mut self, currencies: &[&str]) -> Self {
+        self.currencies = join_codes(currencies);
+        self
+    }
+
+    /// Restricts the rates to currencies of the given kind, e.g. only cryptocurrencies.
+    pub fn currency_type(mut self, currency_type: models::CurrencyType) -> Self {
+        self.currency_type = Some(currency_type);
+        self
+    }
+
+    /// Constructs the url of the request with the parameters that were set.
+    fn url(&self) -> Result<Url, CurrencyapiError> {
+        let mut url = Endpoint::Latest.url(self.api.base_url())?;
+        if let Some(base_currency) = &self.base_currency {
+            url.query_pairs_mut()
+                .append_pair("base_currency", base_currency);
+        }
+        append_currencies(&mut url, &self.currencies);
+        append_currency_type(&mut url, self.currency_type);
+        Ok(url)
+    }
+
+    /// Sends the request.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<models::LatestResponse, CurrencyapiError>` - A result containing either the latest rates or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    pub async fn send(self) -> Result<models::LatestResponse, CurrencyapiError> {
+        let url = self.url()?;
+        self.api.latest_at(url).await
+    }
+}
+
+#[cfg(test)]
+mod latest_request_test {
+    use super::*;
+    use crate::testing::{MockResponse, MockServer};
+
+    #[test]
+    fn should_only_add_parameters_that_were_set() {
+        let api = Currencyapi::new("key").unwrap();
+        let url = api.latest_builder().url().unwrap();
+        assert_eq!(url.query(), None);
+        let url = api
+            .latest_builder()
+            .base_currency("EUR")
+            .currencies(&["USD", " GBP"])
+            .currency_type(models::CurrencyType::Fiat)
+            .url()
+            .unwrap();
+        assert_eq!(
+            url.query(),
+            Some("base_currency=EUR&currencies=USD%2CGBP&type=fiat")
+        );
+    }
+
+    #[tokio::test]
+    async fn should_send_latest_request() {
+        let server = MockServer::start(|target| {
+            let status = if target == "/latest?base_currency=EUR&currencies=USD" {
+                200
+            } else {
+                404
+            };
+            MockResponse::json(
+                status,
+                r#"{"meta":null,"data":{"USD":{"code":"USD","value":1.08}}}"#,
+            )
+        });
+        let api = Currencyapi::with_base_url("key", server.url.as_str()).unwrap();
+        let res = api
+            .latest_builder()
+            .base_currency("EUR")
+            .currencies(&["USD"])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.data["USD"].value, Some(1.08));
+        assert_eq!(
+            res.meta.and_then(|meta| meta.base_currency).as_deref(),
+            Some("EUR")
+        );
+    }
+}
@@ -1,15 +1,144 @@
 //! Module that contains the main [Currencyapi] struct
 
-use std::sync::Arc;
-use reqwest::Client;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::future::Future;
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use futures::future::{BoxFuture, Either};
+use futures::{FutureExt, StreamExt, TryStreamExt};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, RETRY_AFTER};
+use reqwest::{Client, RequestBuilder, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use tokio::sync::Semaphore;
+use crate::cache::{CacheStats, DataSource, ResponseCache};
+#[cfg(feature = "chrono")]
+use chrono::NaiveDate;
 use crate::error::CurrencyapiError;
-use crate::{error, models, utils};
-use crate::utils::baseline::construct_base_url;
+use crate::latency::{LatencyStats, LatencyWindow};
+use crate::limiter::LeakyBucket;
+use crate::{error, models};
+use crate::utils::{duplicates, json, numbers};
+#[cfg(feature = "tracing")]
+use tracing::field::Empty;
+use crate::{retry, CurrencyAliases, RetryConfig, RoundingMode};
+
+mod builder;
+mod endpoint;
+mod health;
+#[cfg(feature = "validate")]
+pub(crate) mod known_codes;
+mod latest_request;
+mod plan;
+mod quota;
+mod recording;
+mod state;
+mod summary;
+
+pub use builder::CurrencyapiBuilder;
+pub use endpoint::Endpoint;
+pub use health::Health;
+pub use latest_request::LatestRequestBuilder;
+pub use plan::CallPlan;
+pub use quota::QuotaHeaders;
+pub use recording::{Interaction, Recording};
+pub use state::AppState;
+pub use summary::ConfigSummary;
 
 /// Settings struct that contains the api key
 #[derive(Debug, Clone)]
 pub struct Settings {
     api_key: String,
+    base_url: Url,
+    /// Version path segment of the default base url or `None` if a base url was set
+    api_version: Option<String>,
+    number_handling: NumberHandling,
+    non_finite_rates: NonFiniteRates,
+    accept: HeaderValue,
+    aliases: Option<CurrencyAliases>,
+    retry: Option<RetryConfig>,
+    value_precision: Option<usize>,
+    max_convert_value: Option<f64>,
+    on_error: Option<ErrorHookFn>,
+    reject_duplicate_currencies: bool,
+    strict_base_currency: bool,
+    base_fallback: Option<String>,
+    hedge_after: Option<Duration>,
+    timeout: Option<Duration>,
+    clock_skew_tolerance: Duration,
+    server_clock: bool,
+    #[cfg(feature = "chrono")]
+    history_start: NaiveDate,
+    max_concurrency: NonZeroUsize,
+    recording: Option<Recording>,
+    /// Headers added to each request if the http client was provided by the caller
+    request_headers: Option<HeaderMap>,
+    /// User agent of the http client constructed for the api
+    user_agent: Option<String>,
+}
+
+impl Settings {
+    /// Returns the api key sent with every request.
+    pub(crate) fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// Returns the url the endpoint paths are appended to.
+    pub(crate) fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// Returns the time after which requests are aborted, if any.
+    pub(crate) fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+}
+
+/// Callback invoked with every error returned by a request,
+/// see [CurrencyapiBuilder::on_error]
+pub type ErrorHook = Arc<dyn Fn(&CurrencyapiError) + Send + Sync>;
+
+/// Wrapper of an [ErrorHook] that can be part of the derived `Debug` output
+#[derive(Clone)]
+struct ErrorHookFn(ErrorHook);
+
+impl std::fmt::Debug for ErrorHookFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ErrorHook")
+    }
+}
+
+/// Environment variable read by [Currencyapi::from_env]
+const API_KEY_VAR: &str = "CURRENCYAPI_KEY";
+
+/// Controls how numbers in api responses are converted into rates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberHandling {
+    /// Numbers are parsed into the nearest `f64`, silently rounding
+    /// values with more precision than an `f64` can hold
+    #[default]
+    Lossy,
+    /// Numbers are never rounded. `Currencyapi::latest_decimal` parses rates from their
+    /// exact digits, so high-precision rates are preserved, while methods returning `f64`
+    /// rates reject responses containing a number that cannot be represented exactly as
+    /// `f64`. Numbers neither type can hold fail with [CurrencyapiError::PrecisionLoss].
+    Strict,
+}
+
+/// Controls how rates that are NaN or infinite are handled, e.g. rates of a rebased
+/// table whose base rate is too small to invert. Rates the api returned as `false` or
+/// `null` are handled the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFiniteRates {
+    /// Tables containing a non-finite or missing rate are rejected with
+    /// [CurrencyapiError::InvalidResponseData]
+    Reject,
+    /// Currencies with a non-finite or missing rate are removed from the table
+    #[default]
+    Skip,
+    /// Non-finite and missing rates are returned unchanged
+    Keep,
 }
 
 /// The main struct of the crate giving access to the currencyapi.
@@ -18,230 +147,5138 @@ pub struct Settings {
 pub struct Currencyapi {
     client: Client,
     settings: Arc<Settings>,
+    seen_codes: Option<Arc<Mutex<BTreeSet<String>>>>,
+    cache: Option<Arc<ResponseCache>>,
+    limiter: Option<Arc<LeakyBucket>>,
+    latencies: Arc<LatencyWindow>,
+    permits: Arc<Semaphore>,
+    rate_overrides: Arc<Mutex<HashMap<(String, String), f64>>>,
+    quote_scales: Arc<Mutex<HashMap<String, f64>>>,
+    quota: Arc<Mutex<Option<QuotaHeaders>>>,
+    #[cfg(feature = "chrono")]
+    server_offset: Arc<std::sync::atomic::AtomicI64>,
 }
 
-impl<'a> Currencyapi {
+impl Currencyapi {
     /// Creates a new instance of the Currencyapi struct by passing your api key as
     /// function parameter.
-    pub fn new(api_key: &'a str) -> Result<Self, CurrencyapiError> {
-        let settings = std::sync::Arc::new(Settings {
-            api_key: String::from(api_key),
+    pub fn new(api_key: impl AsRef<str>) -> Result<Self, CurrencyapiError> {
+        Self::builder(api_key).build()
+    }
+
+    /// Creates a new instance of the Currencyapi struct with the api key read from the
+    /// `CURRENCYAPI_KEY` environment variable.
+    ///
+    /// ```
+    /// # use currencyapi_rs::Currencyapi;
+    /// std::env::set_var("CURRENCYAPI_KEY", "<your-api-key>");
+    /// let c_api = Currencyapi::from_env()?;
+    /// # Ok::<(), currencyapi_rs::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return [CurrencyapiError::MissingApiKey] if the variable is
+    /// unset or empty and the errors of [CurrencyapiBuilder::build] otherwise.
+    pub fn from_env() -> Result<Self, CurrencyapiError> {
+        Self::from_env_var(API_KEY_VAR)
+    }
+
+    /// Creates a new instance of the Currencyapi struct with the api key read from the
+    /// environment variable `name`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [CurrencyapiError::MissingApiKey] if the variable is
+    /// unset or empty and the errors of [CurrencyapiBuilder::build] otherwise.
+    pub fn from_env_var(name: impl AsRef<str>) -> Result<Self, CurrencyapiError> {
+        let name = name.as_ref();
+        match std::env::var(name) {
+            Ok(api_key) if !api_key.trim().is_empty() => Self::builder(&api_key).build(),
+            _ => Err(CurrencyapiError::MissingApiKey {
+                variable: String::from(name),
+            }),
+        }
+    }
+
+    /// Creates a new instance of the Currencyapi struct that sends all requests to
+    /// `base_url` instead of the currencyapi, e.g. a mock server in integration tests or
+    /// a regional proxy. The endpoint paths are appended to the path of `base_url`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [CurrencyapiError::UrlConstruction] if `base_url` is not
+    /// a valid http url and the errors of [CurrencyapiBuilder::build] otherwise.
+    pub fn with_base_url(
+        api_key: impl AsRef<str>,
+        base_url: impl AsRef<str>,
+    ) -> Result<Self, CurrencyapiError> {
+        Self::builder(api_key).base_url(base_url.as_ref()).build()
+    }
+
+    /// Creates a new instance of the Currencyapi struct that sends requests to the api
+    /// version `version`, e.g. `v4`, see [CurrencyapiBuilder::api_version].
+    ///
+    /// # Errors
+    ///
+    /// This function will return [CurrencyapiError::InvalidConfiguration] if `version` is
+    /// empty or contains a `/` and the errors of [CurrencyapiBuilder::build] otherwise.
+    pub fn with_version(
+        api_key: impl AsRef<str>,
+        version: impl AsRef<str>,
+    ) -> Result<Self, CurrencyapiError> {
+        Self::builder(api_key).api_version(version.as_ref()).build()
+    }
+
+    /// Creates a new instance of the Currencyapi struct that aborts requests not completed
+    /// within `timeout` with [CurrencyapiError::Timeout].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the errors of [CurrencyapiBuilder::build].
+    pub fn with_timeout(
+        api_key: impl AsRef<str>,
+        timeout: Duration,
+    ) -> Result<Self, CurrencyapiError> {
+        Self::builder(api_key).timeout(timeout).build()
+    }
+
+    /// Creates a new instance of the Currencyapi struct that retries transient failures and
+    /// rate limited requests according to `config`, see [CurrencyapiBuilder::retry].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the errors of [CurrencyapiBuilder::build].
+    pub fn with_retry(
+        api_key: impl AsRef<str>,
+        config: RetryConfig,
+    ) -> Result<Self, CurrencyapiError> {
+        Self::builder(api_key).retry(config).build()
+    }
+
+    /// Creates a new instance of the Currencyapi struct that answers repeated requests from
+    /// an in-memory cache for `ttl`, see [CurrencyapiBuilder::cache_ttl].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the errors of [CurrencyapiBuilder::build].
+    pub fn with_cache(api_key: impl AsRef<str>, ttl: Duration) -> Result<Self, CurrencyapiError> {
+        Self::builder(api_key).cache_ttl(ttl).build()
+    }
+
+    /// Creates a new instance of the Currencyapi struct that sends all requests with `client`,
+    /// e.g. to share the connection pool of a service, see [CurrencyapiBuilder::http_client].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the errors of [CurrencyapiBuilder::build].
+    pub fn with_client(client: Client, api_key: impl AsRef<str>) -> Result<Self, CurrencyapiError> {
+        Self::builder(api_key).http_client(client).build()
+    }
+
+    /// Creates a new instance of the Currencyapi struct that sends `user_agent` as
+    /// `User-Agent` header, see [CurrencyapiBuilder::user_agent].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the errors of [CurrencyapiBuilder::build].
+    pub fn with_user_agent(
+        api_key: impl AsRef<str>,
+        user_agent: impl AsRef<str>,
+    ) -> Result<Self, CurrencyapiError> {
+        let api_key = api_key.as_ref();
+        Self::builder(api_key)
+            .user_agent(user_agent.as_ref())
+            .build()
+    }
+
+    /// Creates a [CurrencyapiBuilder] to configure a client with non-default options.
+    pub fn builder(api_key: impl AsRef<str>) -> CurrencyapiBuilder {
+        CurrencyapiBuilder::new(api_key.as_ref())
+    }
+
+    /// Returns the sorted set of all currency codes seen in responses so far.
+    ///
+    /// Codes are only recorded if the client was built with
+    /// [CurrencyapiBuilder::track_seen_codes], otherwise the set is always empty.
+    pub fn seen_codes(&self) -> BTreeSet<String> {
+        self.seen_codes
+            .as_ref()
+            .map(|seen| seen.lock().unwrap_or_else(|err| err.into_inner()).clone())
+            .unwrap_or_default()
+    }
+
+    /// Registers a fixed rate for converting `from` into `to`, e.g. a negotiated corporate
+    /// rate or a deterministic rate in tests. The rate is also used inversely for converting
+    /// `to` into `from` unless that pair has an override of its own.
+    ///
+    /// Overrides are consulted by [Currencyapi::convert_offline] before the rates of the
+    /// table, so converting an overridden pair bypasses live data entirely. Clones of a
+    /// client share the same overrides.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [CurrencyapiError::InvalidConfiguration] if `rate` is not
+    /// a positive finite number.
+    pub fn set_rate_override(
+        &self,
+        from: impl AsRef<str>,
+        to: impl AsRef<str>,
+        rate: f64,
+    ) -> Result<(), CurrencyapiError> {
+        let from = from.as_ref();
+        let to = to.as_ref();
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(CurrencyapiError::InvalidConfiguration {
+                reason: format!("rate override must be a positive number, got {}", rate),
+            });
+        }
+        let mut overrides = self
+            .rate_overrides
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        overrides.insert((String::from(from), String::from(to)), rate);
+        Ok(())
+    }
+
+    /// Removes all rates registered with [Currencyapi::set_rate_override].
+    pub fn clear_rate_overrides(&self) {
+        let mut overrides = self
+            .rate_overrides
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        overrides.clear();
+    }
+
+    /// Registers the number of units the rates of each currency are quoted per from the
+    /// [models::CurrencyMetadata::scale] of `currencies`, replacing previously registered
+    /// scales. The api does not return scales, so `currencies` is usually metadata populated
+    /// by the caller, e.g. for commodities whose rates are sourced per ounce.
+    ///
+    /// The scales are applied by [Currencyapi::convert_offline] to the rates of the table.
+    /// A rate quoted per `scale` units means that one unit of the base currency buys
+    /// `rate * scale` units of the currency. Clones of a client share the same scales.
+    pub fn set_quote_scales(&self, currencies: &models::CurrenciesResponse) {
+        let mut scales = self
+            .quote_scales
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        *scales = currencies
+            .data
+            .keys()
+            .map(|code| (code.clone(), currencies.scale(code)))
+            .filter(|(_, scale)| *scale != 1.0)
+            .collect();
+    }
+
+    /// Converts `amount` from one currency into another like [models::LatestResponse::convert],
+    /// using the rate registered with [Currencyapi::set_rate_override] for the pair instead
+    /// of the table if there is one. No request is sent.
+    ///
+    /// Rates of the table are normalized with the scales registered with
+    /// [Currencyapi::set_quote_scales], currencies without a registered scale use `1.0`.
+    ///
+    /// Returns `None` if the pair has no override and either currency is missing from the
+    /// table or the rate of `from` is zero.
+    pub fn convert_offline(
+        &self,
+        table: &models::LatestResponse,
+        from: impl AsRef<str>,
+        to: impl AsRef<str>,
+        amount: f64,
+    ) -> Option<f64> {
+        let from = from.as_ref();
+        let to = to.as_ref();
+        if let Some(rate) = self.override_rate(from, to) {
+            return Some(amount * rate);
+        }
+        let scales = self
+            .quote_scales
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let scale = |code: &str| scales.get(code).copied().unwrap_or(1.0);
+        table
+            .convert(from, to, amount)
+            .map(|converted| converted * scale(to) / scale(from))
+    }
+
+    /// Returns the override for converting `from` into `to`, falling back to the inverse
+    /// of the override for the reverse pair.
+    fn override_rate(&self, from: &str, to: &str) -> Option<f64> {
+        let overrides = self
+            .rate_overrides
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let key = |from: &str, to: &str| (String::from(from), String::from(to));
+        overrides
+            .get(&key(from, to))
+            .copied()
+            .or_else(|| overrides.get(&key(to, from)).map(|rate| 1.0 / rate))
+    }
+
+    /// Removes all responses from the response cache, so the next requests are sent to the
+    /// api again, e.g. after a rate update was announced. The counters are kept.
+    ///
+    /// Does nothing if the client was built without [CurrencyapiBuilder::cache_ttl].
+    /// Clones of a client share the same cache.
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Returns the hit, miss and eviction counters of the response cache.
+    ///
+    /// All counters are zero if the client was built without [CurrencyapiBuilder::cache_ttl].
+    /// Clones of a client share the same cache and counters.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache
+            .as_ref()
+            .map(|cache| cache.stats())
+            .unwrap_or_default()
+    }
+
+    /// Returns the median, 90th and 99th percentile latency of the most recent requests.
+    ///
+    /// The latency of a request is the time from sending it until its response headers
+    /// arrive, excluding time spent waiting for the [CurrencyapiBuilder::leaky_bucket]
+    /// limiter. Responses answered from the cache are not counted. Clones of a client
+    /// share the same window of recent requests.
+    pub fn latency_stats(&self) -> LatencyStats {
+        self.latencies.stats()
+    }
+
+    /// Returns the effective non-secret options of this client for diagnostics.
+    ///
+    /// The api key is never part of the summary.
+    pub fn config_summary(&self) -> ConfigSummary {
+        let settings = &self.settings;
+        ConfigSummary {
+            base_url: settings.base_url.to_string(),
+            api_version: settings.api_version.clone(),
+            accept: String::from_utf8_lossy(settings.accept.as_bytes()).into_owned(),
+            number_handling: settings.number_handling,
+            non_finite_rates: settings.non_finite_rates,
+            currency_aliases: settings.aliases.is_some(),
+            cache_ttl: self.cache.as_ref().map(|cache| cache.ttl()),
+            cache_capacity: self.cache.as_ref().and_then(|cache| cache.capacity()),
+            retry: settings.retry.clone(),
+            value_precision: settings.value_precision,
+            max_convert_value: settings.max_convert_value,
+            error_hook: settings.on_error.is_some(),
+            reject_duplicate_currencies: settings.reject_duplicate_currencies,
+            strict_base_currency: settings.strict_base_currency,
+            track_seen_codes: self.seen_codes.is_some(),
+            leaky_bucket: self
+                .limiter
+                .as_ref()
+                .map(|limiter| (limiter.rate(), limiter.burst())),
+            base_fallback: settings.base_fallback.clone(),
+            hedge_after: settings.hedge_after,
+            timeout: settings.timeout,
+            clock_skew_tolerance: settings.clock_skew_tolerance,
+            server_clock: settings.server_clock,
+            #[cfg(feature = "chrono")]
+            history_start: settings.history_start,
+            max_concurrency: settings.max_concurrency,
+            recording: settings.recording.is_some(),
+            custom_http_client: settings.request_headers.is_some(),
+            user_agent: settings.user_agent.clone(),
+            features: summary::enabled_features(),
+        }
+    }
+
+    /// Constructs the url of a convert request, rejecting values above the configured
+    /// [CurrencyapiBuilder::max_convert_value].
+    fn convert_url(
+        &self,
+        base_currency: &str,
+        date: &str,
+        value: f64,
+        currencies: &str,
+    ) -> Result<Url, CurrencyapiError> {
+        if let Some(max) = self.settings.max_convert_value {
+            if value.is_nan() || value.abs() > max {
+                return Err(error::CurrencyapiError::ValueOutOfRange { value, max });
+            }
+        }
+        convert_url(
+            self.base_url(),
+            base_currency,
+            date,
+            &self.format_value(value),
+            currencies,
+        )
+    }
+
+    /// Formats a convert amount with the configured [CurrencyapiBuilder::value_precision].
+    fn format_value(&self, value: f64) -> String {
+        match self.settings.value_precision {
+            Some(precision) => format!("{:.*}", precision, value),
+            None => value.to_string(),
+        }
+    }
+
+    /// Returns the url the endpoint paths are appended to.
+    fn base_url(&self) -> &Url {
+        self.settings.base_url()
+    }
+
+    /// Returns the current day for choosing between latest and historical rates, see
+    /// [CurrencyapiBuilder::server_clock] and [CurrencyapiBuilder::clock_skew_tolerance].
+    #[cfg(feature = "chrono")]
+    fn today(&self) -> NaiveDate {
+        let offset = self
+            .server_offset
+            .load(std::sync::atomic::Ordering::Relaxed);
+        let now = chrono::Utc::now() + chrono::TimeDelta::milliseconds(offset);
+        effective_today(now, self.settings.clock_skew_tolerance)
+    }
+
+    /// Stores the offset of the api's clock from the `Date` header of a response if
+    /// [CurrencyapiBuilder::server_clock] is enabled.
+    #[cfg(feature = "chrono")]
+    fn sync_server_clock(&self, headers: &reqwest::header::HeaderMap) {
+        if !self.settings.server_clock {
+            return;
+        }
+        let offset = headers
+            .get(reqwest::header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| server_offset(date, chrono::Utc::now()));
+        if let Some(offset) = offset {
+            self.server_offset.store(
+                offset.num_milliseconds(),
+                std::sync::atomic::Ordering::Relaxed,
+            );
+        }
+    }
+
+    /// Stores the rate limit headers of a response, see [Currencyapi::quota].
+    fn record_quota(&self, headers: &HeaderMap) {
+        if let Some(quota) = QuotaHeaders::from_headers(headers) {
+            *self.quota.lock().unwrap_or_else(|err| err.into_inner()) = Some(quota);
+        }
+    }
+
+    /// Returns the remaining requests reported by the rate limit headers of the most
+    /// recent api response, or `None` if no response contained them yet.
+    ///
+    /// The quota is shared by all clones of the client. Responses answered from the
+    /// cache do not update it.
+    pub fn quota(&self) -> Option<QuotaHeaders> {
+        *self.quota.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Adds the given codes to the seen codes if tracking is enabled.
+    fn record_codes<'c>(&self, codes: impl IntoIterator<Item = &'c String>) {
+        if let Some(seen) = &self.seen_codes {
+            let mut seen = seen.lock().unwrap_or_else(|err| err.into_inner());
+            seen.extend(codes.into_iter().cloned());
+        }
+    }
+
+    /// Creates an authenticated GET request to the given url.
+    ///
+    /// With the `validate` feature, the currency codes of the url are checked against the
+    /// bundled list of known codes after applying [CurrencyapiBuilder::currency_aliases].
+    fn request(&self, url: Url) -> Result<RequestBuilder, CurrencyapiError> {
+        let url = self.normalize_aliases(url);
+        #[cfg(feature = "validate")]
+        known_codes::validate_url(&url)?;
+        let request = self
+            .client
+            .get(url)
+            .header(ACCEPT, self.settings.accept.clone());
+        let Some(headers) = &self.settings.request_headers else {
+            return Ok(request);
+        };
+        let request = request.headers(headers.clone());
+        Ok(match self.settings.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        })
+    }
+
+    /// Replaces deprecated codes in the currency parameters of the url if
+    /// [CurrencyapiBuilder::currency_aliases] is configured.
+    fn normalize_aliases(&self, url: Url) -> Url {
+        let Some(aliases) = &self.settings.aliases else {
+            return url;
+        };
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(key, value)| {
+                let value = match key.as_ref() {
+                    "base_currency" => aliases.normalize_alias(&value).0,
+                    "currencies" => aliases.normalize_list(&value),
+                    _ => value.into_owned(),
+                };
+                (key.into_owned(), value)
+            })
+            .collect();
+        let mut normalized = url;
+        if !pairs.is_empty() {
+            normalized.query_pairs_mut().clear().extend_pairs(pairs);
+        }
+        normalized
+    }
+
+    /// Sends an authenticated GET request to the given url and deserializes the json body.
+    async fn get<T: DeserializeOwned>(&self, url: Url) -> Result<T, CurrencyapiError> {
+        let result = match self.get_body(url).await {
+            Ok(body) => self.parse_body(body),
+            Err(err) => Err(err),
+        };
+        self.report(result)
+    }
+
+    /// Passes the error of a failed request to the [ErrorHook] if one is configured.
+    fn report<T>(&self, result: Result<T, CurrencyapiError>) -> Result<T, CurrencyapiError> {
+        if let (Err(err), Some(hook)) = (&result, &self.settings.on_error) {
+            (hook.0)(err);
+        }
+        result
+    }
+
+    /// Waits until the [CurrencyapiBuilder::leaky_bucket] limiter admits another request.
+    async fn throttle(&self) {
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire().await;
+        }
+    }
+
+    /// Waits until fewer than [CurrencyapiBuilder::max_concurrency] requests are in flight.
+    /// The semaphore is never closed, so a permit is always returned.
+    async fn acquire_permit(&self) -> Option<tokio::sync::SemaphorePermit<'_>> {
+        self.permits.acquire().await.ok()
+    }
+
+    /// Sends an authenticated GET request and returns the response as is.
+    ///
+    /// The [CurrencyapiBuilder::max_concurrency] permit is released once the response
+    /// headers arrive, reading the body is not limited.
+    async fn send_raw(&self, url: Url) -> Result<reqwest::Response, CurrencyapiError> {
+        let request = self.request(url)?;
+        let _permit = self.acquire_permit().await;
+        self.throttle().await;
+        let started = Instant::now();
+        let response = request.send().await;
+        self.latencies.record(started.elapsed());
+        response.map_err(CurrencyapiError::request)
+    }
+
+    /// Returns the response body for the given url from the cache if possible and sends
+    /// an authenticated GET request otherwise.
+    async fn get_body(&self, url: Url) -> Result<String, CurrencyapiError> {
+        let (body, _) = self.get_body_with_source(url).await?;
+        Ok(body)
+    }
+
+    /// Returns the response body like [Currencyapi::get_body] together with its origin.
+    async fn get_body_with_source(
+        &self,
+        url: Url,
+    ) -> Result<(String, DataSource), CurrencyapiError> {
+        let Some(cache) = &self.cache else {
+            let body = self.fetch_body_with_retry(url).await?;
+            return Ok((body, DataSource::Network));
+        };
+        let key = url.to_string();
+        if let Some((body, age)) = cache.get(&key) {
+            return Ok((body, DataSource::Cache { age }));
+        }
+        let body = self.fetch_body_with_retry(url).await?;
+        cache.insert(key, body.clone());
+        Ok((body, DataSource::Network))
+    }
+
+    /// Fetches the response body, retrying transient failures if a [RetryConfig] is set.
+    async fn fetch_body_with_retry(&self, url: Url) -> Result<String, CurrencyapiError> {
+        match &self.settings.retry {
+            Some(config) => {
+                let config = RetryConfig {
+                    deadline: config.deadline.or(self.settings.timeout),
+                    ..config.clone()
+                };
+                retry::retry(&config, || self.fetch_body_hedged(url.clone())).await
+            }
+            None => self.fetch_body_hedged(url).await,
+        }
+    }
+
+    /// Fetches the response body, racing a duplicate request against a slow one
+    /// if [CurrencyapiBuilder::hedge_after] is set.
+    ///
+    /// An error of the first request is returned as is, while an error of the duplicate
+    /// falls back to waiting for the first request.
+    async fn fetch_body_hedged(&self, url: Url) -> Result<String, CurrencyapiError> {
+        let Some(delay) = self.settings.hedge_after else {
+            return self.fetch_body(url).await;
+        };
+        let primary = std::pin::pin!(self.fetch_body(url.clone()));
+        let hedge = std::pin::pin!(async {
+            tokio::time::sleep(delay).await;
+            self.fetch_body(url).await
+        });
+        match futures::future::select(primary, hedge).await {
+            Either::Left((result, _)) => result,
+            Either::Right((Ok(body), _)) => Ok(body),
+            Either::Right((Err(_), primary)) => primary.await,
+        }
+    }
+
+    /// Sends an authenticated GET request to the given url and returns the response body.
+    async fn fetch_body(&self, url: Url) -> Result<String, CurrencyapiError> {
+        let request = self
+            .request(url.clone())?
+            .build()
+            .map_err(CurrencyapiError::request)?;
+        let _permit = self.acquire_permit().await;
+        self.throttle().await;
+        #[cfg(feature = "opentelemetry")]
+        let span = crate::telemetry::start_request_span(
+            &opentelemetry::global::tracer(crate::telemetry::TRACER_NAME),
+            &url,
+        );
+        let pending = self
+            .settings
+            .recording
+            .as_ref()
+            .map(|recording| (recording, recording::PendingInteraction::of(&request)));
+        #[cfg(feature = "tracing")]
+        tracing::debug!(url = %request.url(), "sending request");
+        let started = Instant::now();
+        let response = self.client.execute(request).await;
+        self.latencies.record(started.elapsed());
+        #[cfg(feature = "opentelemetry")]
+        crate::telemetry::end_request_span(
+            span,
+            response.as_ref().map(|response| response.status()),
+        );
+        let response = response.map_err(CurrencyapiError::request)?;
+        let status = response.status();
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("http.status_code", status.as_u16());
+        #[cfg(feature = "chrono")]
+        self.sync_server_clock(response.headers());
+        self.record_quota(response.headers());
+        let headers = pending.as_ref().map(|_| response.headers().clone());
+        let retry_after = retry_after(response.headers());
+        let bytes = response.bytes().await.map_err(CurrencyapiError::request)?;
+        if let (Some((recording, pending)), Some(headers)) = (pending, headers) {
+            let mut recording = recording.lock().unwrap_or_else(|err| err.into_inner());
+            recording.push(pending.complete(status, &headers, &bytes));
+        }
+        let body = String::from_utf8_lossy(&bytes).into_owned();
+        if !status.is_success() {
+            return Err(status_error(&url, status, retry_after, body));
+        }
+        Ok(body)
+    }
+
+    /// Rejects bodies with numbers that lose precision if [NumberHandling::Strict] is configured
+    /// and bodies with duplicate currencies if [CurrencyapiBuilder::reject_duplicate_currencies]
+    /// is enabled.
+    fn check_body(&self, body: &str) -> Result<(), CurrencyapiError> {
+        if self.settings.number_handling == NumberHandling::Strict {
+            numbers::ensure_lossless(body)?;
+        }
+        self.check_duplicates(body)
+    }
+
+    /// Rejects bodies with duplicate currencies if
+    /// [CurrencyapiBuilder::reject_duplicate_currencies] is enabled.
+    fn check_duplicates(&self, body: &str) -> Result<(), CurrencyapiError> {
+        if self.settings.reject_duplicate_currencies {
+            let duplicate = duplicates::find_duplicate_code(body).map_err(|_| {
+                error::CurrencyapiError::ResponseParsingError {
+                    body: String::from(body),
+                }
+            })?;
+            if let Some(code) = duplicate {
+                return Err(error::CurrencyapiError::DuplicateCurrency { code });
+            }
+        }
+        Ok(())
+    }
+
+    /// Deserializes a response body according to the configured [NumberHandling].
+    fn parse_body<T: DeserializeOwned>(&self, body: String) -> Result<T, CurrencyapiError> {
+        self.check_body(&body)?;
+        json::from_str(&body).map_err(|_| error::CurrencyapiError::ResponseParsingError { body })
+    }
+
+    /// Deserializes a response body with [rust_decimal::Decimal] rates, parsing the rates from
+    /// their exact digits if [NumberHandling::Strict] is configured.
+    #[cfg(feature = "decimal")]
+    fn parse_body_decimal<T: DeserializeOwned>(&self, body: String) -> Result<T, CurrencyapiError> {
+        if self.settings.number_handling == NumberHandling::Lossy {
+            return self.parse_body(body);
+        }
+        self.check_duplicates(&body)?;
+        let exact = numbers::quote_exact_decimals(&body)?;
+        json::from_str(&exact).map_err(|_| error::CurrencyapiError::ResponseParsingError { body })
+    }
+
+    /// Parses a response body into a [serde_json::Value] and deserializes `T` from it, keeping
+    /// the value for [Currencyapi::latest_raw].
+    fn parse_body_raw<T: DeserializeOwned>(
+        &self,
+        body: String,
+    ) -> Result<(T, serde_json::Value), CurrencyapiError> {
+        self.check_body(&body)?;
+        let parsed = json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|raw| T::deserialize(&raw).ok().map(|typed| (typed, raw)));
+        parsed.ok_or(error::CurrencyapiError::ResponseParsingError { body })
+    }
+
+    /// Fetches a rate table, falling back to [CurrencyapiBuilder::base_fallback] if the
+    /// base currency of the url is not supported and splitting requests for too many
+    /// currencies, see [Currencyapi::get_rates_split].
+    async fn get_rates(&self, url: Url) -> Result<models::LatestResponse, CurrencyapiError> {
+        let (res, _) = self.get_rates_with_source(url).await?;
+        Ok(res)
+    }
+
+    /// Fetches a rate table like [Currencyapi::get_rates] together with its origin.
+    async fn get_rates_with_source(
+        &self,
+        url: Url,
+    ) -> Result<(models::LatestResponse, DataSource), CurrencyapiError> {
+        let base_currency = query_value(&url, "base_currency");
+        let result = self.get_rates_split(url, 0).await;
+        let result = result.and_then(|(mut res, source)| {
+            apply_non_finite_rates(&mut res, self.settings.non_finite_rates)?;
+            let meta = res.meta.get_or_insert_with(models::Meta::default);
+            let reported = meta.base_currency.clone();
+            if reported.is_none() {
+                meta.base_currency = base_currency.clone();
+            }
+            if meta.fallback_base.is_some() {
+                return Ok((res, source));
+            }
+            let Some(requested) = base_currency else {
+                return Ok((res, source));
+            };
+            let rates = res.data.iter().map(|(code, rate)| (code, rate.value));
+            self.check_base(&requested, reported.as_deref(), rates, 1.0)?;
+            Ok((res, source))
         });
-        let client = utils::baseline::construct_client(None, &settings)?;
-        Ok(Self { client, settings })
+        self.report(result)
+    }
+
+    /// Rejects a response whose base currency differs from `requested` with
+    /// [CurrencyapiError::UnexpectedBaseCurrency] if [CurrencyapiBuilder::strict_base_currency]
+    /// is enabled, see [unexpected_base] for the meaning of `rates` and `unit`.
+    fn check_base<'r>(
+        &self,
+        requested: &str,
+        reported: Option<&str>,
+        rates: impl IntoIterator<Item = (&'r String, Option<f64>)>,
+        unit: f64,
+    ) -> Result<(), CurrencyapiError> {
+        if !self.settings.strict_base_currency {
+            return Ok(());
+        }
+        match unexpected_base(rates, reported, requested, unit) {
+            Some(got) => Err(CurrencyapiError::UnexpectedBaseCurrency {
+                requested: String::from(requested),
+                got,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Fetches a rate table like [Currencyapi::get_rates] without reporting errors. If the
+    /// api rejects the request as too large, the requested currencies are split in half and
+    /// both halves are requested concurrently and merged, up to [MAX_SPLIT_DEPTH] times.
+    fn get_rates_split(
+        &self,
+        url: Url,
+        depth: u32,
+    ) -> BoxFuture<'_, Result<(models::LatestResponse, DataSource), CurrencyapiError>> {
+        async move {
+            let body = self.get_body_with_source(url.clone()).await;
+            if let Err(CurrencyapiError::RequestTooLarge { .. }) = body {
+                if let Some((first, second)) =
+                    split_currencies(&url).filter(|_| depth < MAX_SPLIT_DEPTH)
+                {
+                    let ((first, first_source), (second, second_source)) = futures::try_join!(
+                        self.get_rates_split(first, depth + 1),
+                        self.get_rates_split(second, depth + 1),
+                    )?;
+                    let merged = models::LatestResponse::merge(vec![first, second])?;
+                    return Ok((merged, first_source.combine(second_source)));
+                }
+            }
+            match (body, self.settings.base_fallback.as_deref()) {
+                (Err(CurrencyapiError::UnsupportedBaseCurrency { base }), Some(fallback))
+                    if base != fallback =>
+                {
+                    self.get_rebased(url, &base, fallback).await
+                }
+                (Ok((body, source)), _) => Ok((self.parse_body(body)?, source)),
+                (Err(err), _) => Err(err),
+            }
+        }
+        .boxed()
+    }
+
+    /// Requests the rates of `url` relative to `fallback` and rebases them to `base`.
+    async fn get_rebased(
+        &self,
+        url: Url,
+        base: &str,
+        fallback: &str,
+    ) -> Result<(models::LatestResponse, DataSource), CurrencyapiError> {
+        let requested: Vec<String> = query_value(&url, "currencies")
+            .map(|currencies| split_codes(&currencies))
+            .unwrap_or_default();
+        let mut codes = requested.clone();
+        if !codes.is_empty() && !codes.iter().any(|code| code == base) {
+            codes.push(String::from(base));
+        }
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(key, value)| {
+                let value = match key.as_ref() {
+                    "base_currency" => String::from(fallback),
+                    "currencies" => codes.join(","),
+                    _ => value.into_owned(),
+                };
+                (key.into_owned(), value)
+            })
+            .collect();
+        let mut fallback_url = url;
+        fallback_url.query_pairs_mut().clear().extend_pairs(pairs);
+        let (body, source) = self.get_body_with_source(fallback_url).await?;
+        let table: models::LatestResponse = self.parse_body(body)?;
+        let mut rebased = table.rebase(fallback, base).ok_or_else(|| {
+            error::CurrencyapiError::UnsupportedBaseCurrency {
+                base: String::from(base),
+            }
+        })?;
+        if !requested.is_empty() {
+            rebased.data.retain(|code, _| requested.contains(code));
+        }
+        let meta = rebased.meta.get_or_insert_with(models::Meta::default);
+        meta.fallback_base = Some(String::from(fallback));
+        Ok((rebased, source))
+    }
+
+    /// Fetches the latest rates as a typed [models::LatestResponse].
+    async fn latest_typed(
+        &self,
+        base_currency: &str,
+        currencies: &str,
+    ) -> Result<models::LatestResponse, CurrencyapiError> {
+        let url = latest_url(self.base_url(), base_currency, currencies)?;
+        let res_body = self.get_rates(url).await?;
+        self.record_codes(res_body.data.keys());
+        Ok(res_body)
+    }
+
+    /// Fetches the historical rates of a date as a typed [models::LatestResponse].
+    async fn historical_typed(
+        &self,
+        base_currency: &str,
+        date: &str,
+        currencies: &str,
+    ) -> Result<models::LatestResponse, CurrencyapiError> {
+        let url = historical_url(self.base_url(), base_currency, date, currencies)?;
+        let res_body = self.get_rates(url).await?;
+        self.record_codes(res_body.data.keys());
+        Ok(res_body)
     }
 
-    /// Fetches the status of the currency API.
+    /// Fetches the status of the currency API, i.e. the request quotas of the api key.
     ///
     /// # Returns
     ///
-    /// * `Result<models::DetailsResponse, error::CurrencyapiError>` - A result containing either the details response or a currency API error.
+    /// * `Result<models::StatusResponse, error::CurrencyapiError>` - A result containing either the quotas or a currency API error.
     ///
     /// # Errors
     ///
     /// This function will return an error if the request fails or if the response cannot be parsed.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, err, fields(
+            endpoint = Endpoint::Status.path(),
+            http.status_code = Empty,
+        ))
+    )]
     pub async fn status(
         &self,
-    ) -> Result<models::DetailsResponse, error::CurrencyapiError> {
-        let url = construct_base_url(Some("status"))?;
-        let res_body: models::DetailsResponse = self
-            .client
-            .get(url)
-            .header("apikey", &self.settings.api_key)
-            .send()
-            .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?
-            .json()
-            .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?;
-        Ok(res_body)
+    ) -> Result<models::StatusResponse, error::CurrencyapiError> {
+        let url = Endpoint::Status.url(self.base_url())?;
+        self.get(url).await
+    }
+
+    /// Checks the reachability, the validity of the api key and the remaining quota with
+    /// a status request, e.g. for the liveness or readiness endpoint of a service.
+    ///
+    /// The request bypasses the response cache and retries, so every call reflects the
+    /// current state of the api. Failures are reported in the fields of the result.
+    ///
+    /// # Returns
+    ///
+    /// * `Health` - The health of the api, never an error.
+    pub async fn health(&self) -> Health {
+        match Endpoint::Status.url(self.base_url()) {
+            Ok(url) => self.health_at(url).await,
+            Err(_) => Health::unreachable(),
+        }
+    }
+
+    /// Derives the [Health] from a status request to `url`.
+    async fn health_at(&self, url: Url) -> Health {
+        let Ok(response) = self.send_raw(url).await else {
+            return Health::unreachable();
+        };
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Health::from_response(status, &body)
     }
 
     /// Fetches the list of available currencies.
     ///
+    /// # Arguments
+    ///
+    /// * `currency_type` - An optional kind to restrict the list to, e.g. only cryptocurrencies.
+    ///
     /// # Returns
     ///
-    /// * `Result<models::DetailsResponse, error::CurrencyapiError>` - A result containing either the details response or a currency API error.
+    /// * `Result<models::CurrenciesResponse, error::CurrencyapiError>` - A result containing either the currency metadata or a currency API error.
     ///
     /// # Errors
     ///
     /// This function will return an error if the request fails or if the response cannot be parsed.
     pub async fn currencies(
         &self,
-    ) -> Result<models::DetailsResponse, error::CurrencyapiError> {
-        let url = construct_base_url(Some("currencies"))?;
-        let res_body = self
-            .client
-            .get(url)
-            .header("apikey", &self.settings.api_key)
-            .send()
-            .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?
-            .json()
-            .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?;
+        currency_type: Option<models::CurrencyType>,
+    ) -> Result<models::CurrenciesResponse, error::CurrencyapiError> {
+        let mut url = Endpoint::Currencies.url(self.base_url())?;
+        append_currency_type(&mut url, currency_type);
+        let res_body: models::CurrenciesResponse = self.get(url).await?;
+        self.record_codes(res_body.data.keys());
         Ok(res_body)
     }
 
+    /// Fetches the status, the currency metadata and the latest rates concurrently, e.g. to
+    /// initialize the state of an application on startup.
+    ///
+    /// A failed request does not affect the others, its error is kept in the respective
+    /// field of the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Returns
+    ///
+    /// * `AppState` - The result of each of the three requests.
+    pub async fn init(&self, base_currency: impl AsRef<str>, currencies: &[&str]) -> AppState {
+        let base_currency = base_currency.as_ref();
+        let (status, currencies, latest) = futures::join!(
+            self.status(),
+            self.currencies(None),
+            self.latest(base_currency, currencies, None),
+        );
+        AppState {
+            status,
+            currencies,
+            latest,
+        }
+    }
+
     /// Fetches the latest currency data for the specified base currency and target currencies.
     ///
     /// # Arguments
     ///
     /// * `base_currency` - A string slice that holds the base currency code.
-    /// * `currencies` - A string slice that holds the target currencies.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    /// * `currency_type` - An optional kind to restrict the rates to, e.g. only cryptocurrencies.
     ///
     /// # Returns
     ///
-    /// * `Result<models::DetailsResponse, error::CurrencyapiError>` - A result containing either the details response or a currency API error.
+    /// * `Result<models::LatestResponse, error::CurrencyapiError>` - A result containing either the latest rates or a currency API error.
     ///
     /// # Errors
     ///
     /// This function will return an error if the request fails or if the response cannot be parsed.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, err, fields(
+            endpoint = Endpoint::Latest.path(),
+            base_currency = base_currency.as_ref(),
+            http.status_code = Empty,
+        ))
+    )]
     pub async fn latest(
         &self,
-        base_currency: &'a str,
-        currencies: &'a str,
-    ) -> Result<models::DetailsResponse, error::CurrencyapiError> {
-        let mut url = construct_base_url(Some("latest"))?;
-        url.query_pairs_mut()
-            .append_pair("base_currency", base_currency)
-            .append_pair("currencies", currencies);
-        let res_body: models::DetailsResponse = self
-            .client
-            .get(url)
-            .header("apikey", &self.settings.api_key)
-            .send()
-            .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?
-            .json()
-            .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?;
-        Ok(res_body)
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+        currency_type: Option<models::CurrencyType>,
+    ) -> Result<models::LatestResponse, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let mut url = latest_url(self.base_url(), base_currency, &join_codes(currencies))?;
+        append_currency_type(&mut url, currency_type);
+        self.latest_at(url).await
     }
 
-    /// Fetches historical currency data for the specified parameters.
+    /// Fetches the latest rates like [Currencyapi::latest] without sending a base currency,
+    /// so the api quotes them against the default base currency of the account, usually `USD`.
     ///
     /// # Arguments
     ///
-    /// * `base_currency` - A string slice that holds the base currency code.
-    /// * `date` - A string slice that holds the date for the historical data.
-    /// * `currencies` - A string slice that holds the target currencies.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    /// * `currency_type` - An optional kind to restrict the rates to, e.g. only cryptocurrencies.
     ///
     /// # Returns
     ///
-    /// * `Result<models::DetailsResponse, error::CurrencyapiError>` - A result containing either the details response or a currency API error.
+    /// * `Result<models::LatestResponse, error::CurrencyapiError>` - A result containing either the latest rates or a currency API error.
     ///
     /// # Errors
     ///
     /// This function will return an error if the request fails or if the response cannot be parsed.
-    pub async fn historical(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, err, fields(
+            endpoint = Endpoint::Latest.path(),
+            http.status_code = Empty,
+        ))
+    )]
+    pub async fn latest_default_base(
         &self,
-        base_currency: &'a str,
-        date: &'a str,
-        currencies: &'a str,
-    ) -> Result<models::DetailsResponse, error::CurrencyapiError> {
-        let mut url = construct_base_url(Some("historical"))?;
-        url.query_pairs_mut()
-            .append_pair("base_currency", base_currency)
-            .append_pair("date", date)
-            .append_pair("currencies", currencies);
-        let res_body = self
-            .client
-            .get(url)
-            .header("apikey", &self.settings.api_key)
-            .send()
-            .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?
-            .json()
-            .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?;
-        Ok(res_body)
+        currencies: &[&str],
+        currency_type: Option<models::CurrencyType>,
+    ) -> Result<models::LatestResponse, error::CurrencyapiError> {
+        let mut url = Endpoint::Latest.url(self.base_url())?;
+        append_currencies(&mut url, &join_codes(currencies));
+        append_currency_type(&mut url, currency_type);
+        self.latest_at(url).await
     }
 
-    /// Converts a value from the base currency to the target currencies for the specified date.
+    /// Creates a [LatestRequestBuilder] to fetch the latest rates with optional parameters.
+    pub fn latest_builder(&self) -> LatestRequestBuilder<'_> {
+        LatestRequestBuilder::new(self)
+    }
+
+    /// Fetches the latest exchange rate of a single currency pair, i.e. the amount of
+    /// `quote` one unit of `base_currency` buys.
     ///
     /// # Arguments
     ///
     /// * `base_currency` - A string slice that holds the base currency code.
-    /// * `date` - A string slice that holds the date for the conversion.
-    /// * `value` - An integer that holds the value to be converted.
-    /// * `currencies` - A string slice that holds the target currencies.
+    /// * `quote` - A string slice that holds the quote currency code.
     ///
     /// # Returns
     ///
-    /// * `Result<models::DetailsResponse, error::CurrencyapiError>` - A result containing either the details response or a currency API error.
+    /// * `Result<f64, error::CurrencyapiError>` - A result containing either the rate or a currency API error.
     ///
     /// # Errors
     ///
-    /// This function will return an error if the request fails or if the response cannot be parsed.
-    pub async fn convert(
+    /// This function will return an error if the request fails, if the response cannot be parsed
+    /// or if `quote` or its rate is missing from the response.
+    pub async fn rate(
         &self,
-        base_currency: &'a str,
-        date: &'a str,
-        value: i8,
-        currencies: &'a str,
-    ) -> Result<models::DetailsResponse, error::CurrencyapiError> {
-        let mut url = construct_base_url(Some("convert"))?;
-        url.query_pairs_mut()
-            .append_pair("base_currency", base_currency)
-            .append_pair("date", date)
-            .append_pair("value", &value.to_string())
-            .append_pair("currencies", currencies);
-        let res_body: models::DetailsResponse = self
-            .client
-            .get(url)
-            .header("apikey", &self.settings.api_key)
-            .send()
-            .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?
-            .json()
-            .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?;
-        Ok(res_body)
+        base_currency: impl AsRef<str>,
+        quote: impl AsRef<str>,
+    ) -> Result<f64, error::CurrencyapiError> {
+        let quote = quote.as_ref();
+        let base_currency = base_currency.as_ref();
+        self.latest_typed(base_currency, quote)
+            .await?
+            .data
+            .get(quote)
+            .and_then(|rate| rate.value)
+            .ok_or_else(|| error::CurrencyapiError::CurrencyNotFound {
+                code: String::from(quote),
+            })
     }
 
-    /// Fetches the range of currency data for the specified parameters.
+    /// Converts `amount` from `base_currency` into `quote` with the latest rate, which is
+    /// fetched like [Currencyapi::rate] and applied locally.
     ///
     /// # Arguments
     ///
     /// * `base_currency` - A string slice that holds the base currency code.
-    /// * `datetime_start` - A string slice that holds the start datetime for the range.
-    /// * `datetime_end` - A string slice that holds the end datetime for the range.
-    /// * `currencies` - A string slice that holds the target currencies.
-    /// * `accuracy` - A string slice that holds the accuracy level.
+    /// * `amount` - The amount in the base currency.
+    /// * `quote` - A string slice that holds the quote currency code.
     ///
     /// # Returns
     ///
-    /// * `Result<models::DetailsResponse, error::CurrencyapiError>` - A result containing either the details response or a currency API error.
+    /// * `Result<f64, error::CurrencyapiError>` - A result containing either the converted amount or a currency API error.
     ///
     /// # Errors
     ///
-    /// This function will return an error if the request fails or if the response cannot be parsed.
-    pub async fn range(
+    /// This function will return an error if `amount` is not a finite number, if the request
+    /// fails, if the response cannot be parsed or if `quote` or its rate is missing from the response.
+    pub async fn convert_now(
         &self,
-        base_currency: &'a str,
-        datetime_start: &'a str,
-        datetime_end: &'a str,
-        currencies: &'a str,
-        accuracy: &'a str,
+        base_currency: impl AsRef<str>,
+        amount: f64,
+        quote: impl AsRef<str>,
+    ) -> Result<f64, error::CurrencyapiError> {
+        let quote = quote.as_ref();
+        let base_currency = base_currency.as_ref();
+        if !amount.is_finite() {
+            return Err(error::CurrencyapiError::InvalidAmount { value: amount });
+        }
+        Ok(amount * self.rate(base_currency, quote).await?)
+    }
+
+    /// Fetches the latest rates from `url`, which contains all query parameters.
+    async fn latest_at(&self, url: Url) -> Result<models::LatestResponse, CurrencyapiError> {
+        let res_body = self.get_rates(url).await?;
+        self.record_codes(res_body.data.keys());
+        Ok(res_body)
+    }
+
+    /// Fetches the latest rates like [Currencyapi::latest] and reports whether they were
+    /// received from the api or answered from the response cache, e.g. to debug stale data.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(models::LatestResponse, DataSource), error::CurrencyapiError>` - A result containing either the latest rates with their origin or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    pub async fn latest_with_source(
+        &self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+    ) -> Result<(models::LatestResponse, DataSource), error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let url = latest_url(self.base_url(), base_currency, &join_codes(currencies))?;
+        let (res_body, source) = self.get_rates_with_source(url).await?;
+        self.record_codes(res_body.data.keys());
+        Ok((res_body, source))
+    }
+
+    /// Fetches the latest rates like [Currencyapi::latest] together with the raw json payload,
+    /// e.g. to read fields the api added that [models::LatestResponse] does not model yet.
+    ///
+    /// The body is parsed into a [serde_json::Value] once and the typed response is read from
+    /// that value. Requests for too many currencies are not split and the
+    /// [CurrencyapiBuilder::base_fallback] is not applied, so the raw value always matches
+    /// exactly one response; the [NonFiniteRates] policy still applies to the typed rates.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(models::LatestResponse, serde_json::Value), error::CurrencyapiError>` - A result containing either the latest rates with the raw json payload or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    pub async fn latest_raw(
+        &self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+    ) -> Result<(models::LatestResponse, serde_json::Value), error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let url = latest_url(self.base_url(), base_currency, &join_codes(currencies))?;
+        let result = match self.get_body(url).await {
+            Ok(body) => self.parse_body_raw(body),
+            Err(err) => Err(err),
+        };
+        let (mut res_body, raw) = self.report(result)?;
+        apply_non_finite_rates(&mut res_body, self.settings.non_finite_rates)?;
+        self.record_codes(res_body.data.keys());
+        Ok((res_body, raw))
+    }
+
+    /// Fetches the latest rates like [Currencyapi::latest] with [rust_decimal::Decimal] rates,
+    /// e.g. to sum many converted amounts without floating point rounding errors.
+    ///
+    /// With [NumberHandling::Strict] the rates are parsed from their exact digits, otherwise
+    /// they are rounded to the nearest `f64` first.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<models::LatestResponseDecimal, error::CurrencyapiError>` - A result containing either the latest rates or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    #[cfg(feature = "decimal")]
+    pub async fn latest_decimal(
+        &self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+    ) -> Result<models::LatestResponseDecimal, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let url = latest_url(self.base_url(), base_currency, &join_codes(currencies))?;
+        let result = match self.get_body(url).await {
+            Ok(body) => self.parse_body_decimal(body),
+            Err(err) => Err(err),
+        };
+        let res_body: models::LatestResponseDecimal = self.report(result)?;
+        self.record_codes(res_body.data.keys());
+        Ok(res_body)
+    }
+
+    /// Fetches historical currency data for the specified parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `date` - A string slice that holds the date for the historical data as `YYYY-MM-DD`.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<models::DetailsResponse, error::CurrencyapiError>` - A result containing either the details response or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [CurrencyapiError::InvalidDate] without sending a request
+    /// if `date` is not a valid date and an error if the request fails or if the response
+    /// cannot be parsed.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, err, fields(
+            endpoint = Endpoint::Historical.path(),
+            base_currency = base_currency.as_ref(),
+            http.status_code = Empty,
+        ))
+    )]
+    pub async fn historical(
+        &self,
+        base_currency: impl AsRef<str>,
+        date: impl AsRef<str>,
+        currencies: &[&str],
     ) -> Result<models::DetailsResponse, error::CurrencyapiError> {
-        let mut url = construct_base_url(Some("range"))?;
+        let base_currency = base_currency.as_ref();
+        let date = date.as_ref();
+        let url = historical_url(
+            self.base_url(),
+            base_currency,
+            date,
+            &join_codes(currencies),
+        )?;
+        let res_body: models::DetailsResponse = self.get(url).await?;
+        let reported = res_body
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.get("base_currency"))
+            .and_then(serde_json::Value::as_str);
+        let rates = res_body
+            .data
+            .iter()
+            .map(|(code, rate)| (code, rate.get("value").and_then(serde_json::Value::as_f64)));
+        self.report(self.check_base(base_currency, reported, rates, 1.0))?;
+        self.record_codes(res_body.data.keys());
+        Ok(res_body)
+    }
+
+    /// Fetches historical currency data like [Currencyapi::historical] for a typed date.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `date` - The date for the historical data.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<models::DetailsResponse, error::CurrencyapiError>` - A result containing either the details response or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    #[cfg(feature = "chrono")]
+    pub async fn historical_on(
+        &self,
+        base_currency: impl AsRef<str>,
+        date: NaiveDate,
+        currencies: &[&str],
+    ) -> Result<models::DetailsResponse, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let date = date.format("%Y-%m-%d").to_string();
+        self.historical(base_currency, &date, currencies).await
+    }
+
+    /// Streams the historical rates of every day from `start` to `end`, e.g. to backfill
+    /// a time series without looping over [Currencyapi::historical_on].
+    ///
+    /// Days are requested lazily while the stream is polled, with at most
+    /// [CurrencyapiBuilder::max_concurrency] requests in flight. The rates are yielded in
+    /// chronological order regardless of the order the responses arrive in.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    /// * `start` - The first day of the range.
+    /// * `end` - The last day of the range, included in the stream.
+    ///
+    /// # Returns
+    ///
+    /// * `impl Stream<Item = Result<(NaiveDate, models::LatestResponse), error::CurrencyapiError>>` - A stream of the rates of each day together with the day, which is empty if `start` is after `end`.
+    ///
+    /// # Errors
+    ///
+    /// An item is an error if the request of its day fails or if the response cannot be parsed.
+    /// The stream continues with the following days.
+    #[cfg(feature = "chrono")]
+    pub fn historical_stream<'s>(
+        &'s self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl futures::Stream<
+        Item = Result<(NaiveDate, models::LatestResponse), error::CurrencyapiError>,
+    > + 's {
+        let base_currency = String::from(base_currency.as_ref());
+        let codes = join_codes(currencies);
+        futures::stream::iter(start.iter_days().take_while(move |date| *date <= end))
+            .map(move |date| {
+                let base_currency = base_currency.clone();
+                let codes = codes.clone();
+                async move {
+                    let date_param = date.format("%Y-%m-%d").to_string();
+                    let res_body = self
+                        .historical_typed(&base_currency, &date_param, &codes)
+                        .await?;
+                    Ok((date, res_body))
+                }
+            })
+            .buffered(self.settings.max_concurrency.get())
+    }
+
+    /// Fetches the historical rates of several dates, e.g. to backfill specific days.
+    ///
+    /// Each date is requested independently with at most `concurrency` requests in flight
+    /// at once, so a failing date does not affect the others. Identical requests are
+    /// answered from the response cache if [CurrencyapiBuilder::cache_ttl] is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    /// * `dates` - A slice of the dates to fetch.
+    /// * `concurrency` - The maximum number of concurrent requests, at least one is used.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(NaiveDate, Result<models::LatestResponse, error::CurrencyapiError>)>` - The rates or the error of each date in the order of `dates`.
+    #[cfg(feature = "chrono")]
+    pub async fn historical_batch(
+        &self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+        dates: &[NaiveDate],
+        concurrency: usize,
+    ) -> Vec<(
+        NaiveDate,
+        Result<models::LatestResponse, error::CurrencyapiError>,
+    )> {
+        let base_currency = base_currency.as_ref();
+        let codes = join_codes(currencies);
+        let mut results: Vec<_> =
+            futures::stream::iter(dates.iter().enumerate().map(|(index, date)| {
+                let codes = &codes;
+                async move {
+                    let date_param = date.format("%Y-%m-%d").to_string();
+                    let result = self
+                        .historical_typed(base_currency, &date_param, codes)
+                        .await;
+                    (index, *date, result)
+                }
+            }))
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await;
+        results.sort_by_key(|(index, _, _)| *index);
+        results
+            .into_iter()
+            .map(|(_, date, result)| (date, result))
+            .collect()
+    }
+
+    /// Fetches the historical rates of several dates labeled with an identifier of the
+    /// caller's choice, like [Currencyapi::historical_batch].
+    ///
+    /// Each date is requested independently with at most `concurrency` requests in flight
+    /// at once, so a failing date does not affect the others. If an identifier appears
+    /// more than once, only one of its results is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    /// * `items` - A slice of identifiers with the date to fetch.
+    /// * `concurrency` - The maximum number of concurrent requests, at least one is used.
+    ///
+    /// # Returns
+    ///
+    /// * `HashMap<Id, Result<models::LatestResponse, error::CurrencyapiError>>` - The rates or the error of each date keyed by its identifier.
+    #[cfg(feature = "chrono")]
+    pub async fn historical_batch_keyed<Id: Eq + Hash + Clone>(
+        &self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+        items: &[(Id, NaiveDate)],
+        concurrency: usize,
+    ) -> HashMap<Id, Result<models::LatestResponse, error::CurrencyapiError>> {
+        let base_currency = base_currency.as_ref();
+        let codes = &join_codes(currencies);
+        run_keyed(items, concurrency, |date| async move {
+            let date_param = date.format("%Y-%m-%d").to_string();
+            self.historical_typed(base_currency, &date_param, codes)
+                .await
+        })
+        .await
+    }
+
+    /// Converts a value from the base currency to the target currencies for the specified date.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `date` - A string slice that holds the date for the conversion as `YYYY-MM-DD`,
+    ///   or an empty string for the latest rates.
+    /// * `value` - The amount to convert, e.g. `1499.99`.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<models::ConversionResult, error::CurrencyapiError>` - A result containing either the converted values or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails, if the response cannot be parsed,
+    /// if `date` is not a valid date or if `value` exceeds the [CurrencyapiBuilder::max_convert_value].
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, err, fields(
+            endpoint = Endpoint::Convert.path(),
+            base_currency = base_currency.as_ref(),
+            http.status_code = Empty,
+        ))
+    )]
+    pub async fn convert(
+        &self,
+        base_currency: impl AsRef<str>,
+        date: impl AsRef<str>,
+        value: f64,
+        currencies: &[&str],
+    ) -> Result<models::ConversionResult, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let date = date.as_ref();
+        let url = self.convert_url(base_currency, date, value, &join_codes(currencies))?;
+        let res_body: models::ConversionResult = self.get(url).await?;
+        let reported = res_body
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.base_currency.as_deref());
+        let amounts = res_body.data.iter().map(|(code, rate)| (code, rate.value));
+        self.report(self.check_base(base_currency, reported, amounts, value))?;
+        self.record_codes(res_body.data.keys());
+        Ok(res_body)
+    }
+
+    /// Converts a value like [Currencyapi::convert] with the rates of a typed date.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `date` - The date of the rates used for the conversion.
+    /// * `value` - The amount to convert, e.g. `1499.99`.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<models::ConversionResult, error::CurrencyapiError>` - A result containing either the converted values or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails, if the response cannot be parsed
+    /// or if `value` exceeds the [CurrencyapiBuilder::max_convert_value].
+    #[cfg(feature = "chrono")]
+    pub async fn convert_on(
+        &self,
+        base_currency: impl AsRef<str>,
+        date: NaiveDate,
+        value: f64,
+        currencies: &[&str],
+    ) -> Result<models::ConversionResult, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let date = date.format("%Y-%m-%d").to_string();
+        self.convert(base_currency, &date, value, currencies).await
+    }
+
+    /// Fetches the range of currency data for the specified parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `datetime_start` - A string slice that holds the start datetime for the range.
+    /// * `datetime_end` - A string slice that holds the end datetime for the range.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    /// * `accuracy` - The interval between the rates, e.g. one rate per day.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<models::RangeResponse, error::CurrencyapiError>` - A result containing either the rates ordered by datetime or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, err, fields(
+            endpoint = Endpoint::Range.path(),
+            base_currency = base_currency.as_ref(),
+            http.status_code = Empty,
+        ))
+    )]
+    pub async fn range(
+        &self,
+        base_currency: impl AsRef<str>,
+        datetime_start: impl AsRef<str>,
+        datetime_end: impl AsRef<str>,
+        currencies: &[&str],
+        accuracy: models::RangeAccuracy,
+    ) -> Result<models::RangeResponse, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let datetime_start = datetime_start.as_ref();
+        let datetime_end = datetime_end.as_ref();
+        let url = range_url(
+            self.base_url(),
+            base_currency,
+            datetime_start,
+            datetime_end,
+            &join_codes(currencies),
+            accuracy,
+        )?;
+        let res_body: models::RangeResponse = self.get(url).await?;
+        for rates in res_body.data.values() {
+            let rates = rates.iter().map(|(code, rate)| (code, rate.value));
+            self.report(self.check_base(base_currency, None, rates, 1.0))?;
+        }
+        Ok(res_body)
+    }
+
+    /// Sends a latest request and returns the raw [reqwest::Response], e.g. to consume
+    /// the body with `bytes_stream()`.
+    ///
+    /// This is an escape hatch for advanced use cases: the response cache, retries,
+    /// error hooks and status code handling of the typed methods do not apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request cannot be sent.
+    pub async fn latest_stream_response(
+        &self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+    ) -> Result<reqwest::Response, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let currencies = &join_codes(currencies);
+        let url = latest_url(self.base_url(), base_currency, currencies)?;
+        self.send_raw(url).await
+    }
+
+    /// Sends a historical request and returns the raw [reqwest::Response], e.g. to consume
+    /// the body with `bytes_stream()`.
+    ///
+    /// This is an escape hatch for advanced use cases: the response cache, retries,
+    /// error hooks and status code handling of the typed methods do not apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `date` - A string slice that holds the date for the historical data.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request cannot be sent.
+    pub async fn historical_stream_response(
+        &self,
+        base_currency: impl AsRef<str>,
+        date: impl AsRef<str>,
+        currencies: &[&str],
+    ) -> Result<reqwest::Response, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let date = date.as_ref();
+        let currencies = &join_codes(currencies);
+        let url = historical_url(self.base_url(), base_currency, date, currencies)?;
+        self.send_raw(url).await
+    }
+
+    /// Sends a range request and returns the raw [reqwest::Response], e.g. to consume
+    /// the body of large ranges with `bytes_stream()`.
+    ///
+    /// This is an escape hatch for advanced use cases: the response cache, retries,
+    /// error hooks and status code handling of the typed methods do not apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `datetime_start` - A string slice that holds the start datetime for the range.
+    /// * `datetime_end` - A string slice that holds the end datetime for the range.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    /// * `accuracy` - The interval between the rates, e.g. one rate per day.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request cannot be sent.
+    pub async fn range_stream_response(
+        &self,
+        base_currency: impl AsRef<str>,
+        datetime_start: impl AsRef<str>,
+        datetime_end: impl AsRef<str>,
+        currencies: &[&str],
+        accuracy: models::RangeAccuracy,
+    ) -> Result<reqwest::Response, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let datetime_start = datetime_start.as_ref();
+        let datetime_end = datetime_end.as_ref();
+        let currencies = &join_codes(currencies);
+        let url = range_url(
+            self.base_url(),
+            base_currency,
+            datetime_start,
+            datetime_end,
+            currencies,
+            accuracy,
+        )?;
+        self.send_raw(url).await
+    }
+
+    /// Converts a value from the base currency to the target currency using the latest
+    /// rate and rounds the result with the given [RoundingMode].
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `to` - A string slice that holds the target currency code.
+    /// * `value` - The amount in the base currency.
+    /// * `mode` - The rounding mode applied to the converted amount.
+    /// * `decimals` - The number of decimal places to round to.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<f64, error::CurrencyapiError>` - A result containing either the rounded amount or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails, if the response cannot be parsed
+    /// or if the target currency is missing from the response.
+    pub async fn convert_rounded_mode(
+        &self,
+        base_currency: impl AsRef<str>,
+        to: impl AsRef<str>,
+        value: f64,
+        mode: RoundingMode,
+        decimals: u32,
+    ) -> Result<f64, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let to = to.as_ref();
+        let res_body = self.latest(base_currency, &[to], None).await?;
+        let rate = res_body
+            .data
+            .get(to)
+            .and_then(|rate| rate.value)
+            .ok_or_else(|| error::CurrencyapiError::CurrencyNotFound {
+                code: String::from(to),
+            })?;
+        Ok(mode.round(value * rate, decimals))
+    }
+
+    /// Fetches the latest rates and deserializes the response into a type of the caller's
+    /// choice, e.g. [models::FixedRates] with named fields for a fixed set of currencies.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<T, error::CurrencyapiError>` - A result containing either the deserialized response or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be
+    /// deserialized into `T`.
+    pub async fn latest_as<T: DeserializeOwned>(
+        &self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+    ) -> Result<T, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let currencies = &join_codes(currencies);
+        let url = latest_url(self.base_url(), base_currency, currencies)?;
+        self.get(url).await
+    }
+
+    /// Fetches the latest rates and passes each currency code and rate to `sink`
+    /// while parsing, without collecting them into a map first.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    /// * `sink` - A callback invoked once per currency with its code and rate.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), error::CurrencyapiError>` - An empty result or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    /// The sink may already have been called for some currencies if parsing fails midway.
+    pub async fn latest_into(
+        &self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+        sink: impl FnMut(&str, f64),
+    ) -> Result<(), error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let currencies = &join_codes(currencies);
+        let url = latest_url(self.base_url(), base_currency, currencies)?;
+        let result = match self.get_body(url).await {
+            Ok(body) => self.check_body(&body).and_then(|_| {
+                models::latest::for_each_rate(&body, sink)
+                    .map_err(|_| error::CurrencyapiError::ResponseParsingError { body })
+            }),
+            Err(err) => Err(err),
+        };
+        self.report(result)
+    }
+
+    /// Converts an amount given in minor units of the base currency (e.g. cents) into
+    /// minor units of the target currency using the latest rate.
+    ///
+    /// The number of minor units per major unit is taken from the `decimal_digits` of the
+    /// currency metadata and the result is rounded with [RoundingMode::HalfEven].
+    /// Results beyond the range of `i64` saturate at its bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `to` - A string slice that holds the target currency code.
+    /// * `value_minor` - The amount in minor units of the base currency.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<i64, error::CurrencyapiError>` - A result containing either the amount in minor units of the target currency or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a request fails, if a response cannot be parsed
+    /// or if the metadata or rate of a currency is missing.
+    pub async fn convert_minor_units(
+        &self,
+        base_currency: impl AsRef<str>,
+        to: impl AsRef<str>,
+        value_minor: i64,
+    ) -> Result<i64, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let to = to.as_ref();
+        let mut url = Endpoint::Currencies.url(self.base_url())?;
+        url.query_pairs_mut()
+            .append_pair("currencies", &format!("{},{}", base_currency, to));
+        let metadata: models::CurrenciesResponse = self.get(url).await?;
+        let decimal_digits = |code: &str| {
+            metadata
+                .data
+                .get(code)
+                .map(|currency| currency.decimal_digits as i32)
+                .ok_or_else(|| error::CurrencyapiError::CurrencyNotFound {
+                    code: String::from(code),
+                })
+        };
+        let base_digits = decimal_digits(base_currency)?;
+        let to_digits = decimal_digits(to)?;
+        let rate = if base_currency == to {
+            1.0
+        } else {
+            self.latest_typed(base_currency, to)
+                .await?
+                .data
+                .get(to)
+                .and_then(|rate| rate.value)
+                .ok_or_else(|| error::CurrencyapiError::CurrencyNotFound {
+                    code: String::from(to),
+                })?
+        };
+        Ok(convert_minor(value_minor, base_digits, to_digits, rate))
+    }
+
+    /// Computes the percentage deviation of the latest rates from the rates of a baseline date.
+    ///
+    /// The latest and historical rates are fetched concurrently. Currencies missing from
+    /// either response are excluded from the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `baseline_date` - A string slice that holds the date of the baseline rates.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<HashMap<String, f64>, error::CurrencyapiError>` - A result containing either the deviation in percent per currency or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a request fails or if a response cannot be parsed.
+    pub async fn deviation_from(
+        &self,
+        base_currency: impl AsRef<str>,
+        baseline_date: impl AsRef<str>,
+        currencies: &[&str],
+    ) -> Result<HashMap<String, f64>, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let baseline_date = baseline_date.as_ref();
+        let currencies = &join_codes(currencies);
+        let (latest, baseline) = futures::try_join!(
+            self.latest_typed(base_currency, currencies),
+            self.historical_typed(base_currency, baseline_date, currencies),
+        )?;
+        Ok(latest.deviation_from(&baseline))
+    }
+
+    /// Computes the change of the rate of `quote` in `base_currency` from the date `from`
+    /// to the date `to`, e.g. for day-over-day changes. The rates of both dates are
+    /// fetched concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `quote` - A string slice that holds the quote currency code.
+    /// * `from` - The date of the rate the change is computed from.
+    /// * `to` - The date of the rate the change is computed to.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<models::ChangeResult, error::CurrencyapiError>` - A result containing either both rates with their absolute and percentage change or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a request fails, if a response cannot be parsed,
+    /// if `quote` or its rate is missing from a response or if the rate on `from` is zero.
+    #[cfg(feature = "chrono")]
+    pub async fn change(
+        &self,
+        base_currency: impl AsRef<str>,
+        quote: impl AsRef<str>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<models::ChangeResult, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let quote = quote.as_ref();
+        let from_date = from.format("%Y-%m-%d").to_string();
+        let to_date = to.format("%Y-%m-%d").to_string();
+        let (from_rates, to_rates) = futures::try_join!(
+            self.historical_typed(base_currency, &from_date, quote),
+            self.historical_typed(base_currency, &to_date, quote),
+        )?;
+        let rate_of = |rates: models::LatestResponse| {
+            rates
+                .data
+                .get(quote)
+                .and_then(|rate| rate.value)
+                .ok_or_else(|| error::CurrencyapiError::CurrencyNotFound {
+                    code: String::from(quote),
+                })
+        };
+        let (from_rate, to_rate) = (rate_of(from_rates)?, rate_of(to_rates)?);
+        if from_rate == 0.0 {
+            return Err(error::CurrencyapiError::InvalidResponseData {
+                reason: format!("rate of {} on {} is zero", quote, from_date),
+            });
+        }
+        Ok(models::ChangeResult {
+            from_rate,
+            to_rate,
+            absolute: to_rate - from_rate,
+            percentage: (to_rate - from_rate) / from_rate * 100.0,
+        })
+    }
+
+    /// Returns the currencies whose latest rates changed the most compared to the rates
+    /// of `lookback` ago.
+    ///
+    /// The lookback is rounded down to whole days. If it ends on a weekend, the rates of
+    /// the preceding Friday are used. The latest and historical rates are fetched concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    /// * `lookback` - The time span to compare the latest rates against.
+    /// * `limit` - The maximum number of currencies to return.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<models::Mover>, error::CurrencyapiError>` - A result containing either the movers ordered by absolute change or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a request fails or if a response cannot be parsed.
+    #[cfg(feature = "chrono")]
+    pub async fn top_movers(
+        &self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+        lookback: std::time::Duration,
+        limit: usize,
+    ) -> Result<Vec<models::Mover>, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let currencies = &join_codes(currencies);
+        let today = self.today();
+        let date = lookback_date(today, lookback, self.settings.history_start)
+            .format("%Y-%m-%d")
+            .to_string();
+        let (latest, baseline) = futures::try_join!(
+            self.latest_typed(base_currency, currencies),
+            self.historical_typed(base_currency, &date, currencies),
+        )?;
+        Ok(latest.top_movers(&baseline, limit))
+    }
+
+    /// Fetches the daily rates of the last `days` days up to and including today.
+    ///
+    /// Today's rates are taken from `latest` and the previous days from `historical`. All
+    /// days are requested concurrently, limited by [CurrencyapiBuilder::max_concurrency].
+    /// The window is clamped so it does not start before [CurrencyapiBuilder::history_start].
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `currencies` - A slice of the target currency codes, or an empty slice for all currencies.
+    /// * `days` - The number of days in the window.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<BTreeMap<NaiveDate, HashMap<String, f64>>, error::CurrencyapiError>` - A result containing either the rates per currency ordered by date or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a request fails or if a response cannot be parsed.
+    #[cfg(feature = "chrono")]
+    pub async fn trailing(
+        &self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+        days: u16,
+    ) -> Result<BTreeMap<NaiveDate, HashMap<String, f64>>, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let currencies = &join_codes(currencies);
+        let today = self.today();
+        let days = trailing_dates(today, days, self.settings.history_start);
+        let rates = futures::future::try_join_all(days.into_iter().map(|date| async move {
+            let res_body = if date == today {
+                self.latest_typed(base_currency, currencies).await?
+            } else {
+                let date_param = date.format("%Y-%m-%d").to_string();
+                self.historical_typed(base_currency, &date_param, currencies)
+                    .await?
+            };
+            let rates = res_body
+                .data
+                .into_iter()
+                .filter_map(|(code, rate)| Some((code, rate.value?)))
+                .collect();
+            Ok::<_, CurrencyapiError>((date, rates))
+        }))
+        .await?;
+        Ok(rates.into_iter().collect())
+    }
+
+    /// Converts many `(from, to, amount)` pairs using the latest rates.
+    ///
+    /// Pairs are grouped by their base currency so only one `latest` request is sent per
+    /// distinct base, with at most `max_concurrency` requests in flight at once. The
+    /// conversion itself happens locally.
+    ///
+    /// # Arguments
+    ///
+    /// * `pairs` - A slice of `(from, to, amount)` triples to convert.
+    /// * `max_concurrency` - The maximum number of concurrent requests, at least one is used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<f64>, error::CurrencyapiError>` - A result containing either the converted amounts in the order of `pairs` or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any request fails, if a response cannot be parsed
+    /// or if a target currency is missing from its response.
+    pub async fn convert_pairs(
+        &self,
+        pairs: &[(String, String, f64)],
+        max_concurrency: usize,
+    ) -> Result<Vec<f64>, error::CurrencyapiError> {
+        let groups = group_pairs_by_base(pairs);
+        let tables: BTreeMap<String, models::LatestResponse> =
+            futures::stream::iter(groups.into_iter().map(|(base, targets)| async move {
+                let res_body = self.latest_typed(&base, &targets.join(",")).await?;
+                Ok::<_, CurrencyapiError>((base, res_body))
+            }))
+            .buffer_unordered(max_concurrency.max(1))
+            .try_collect()
+            .await?;
+        pairs
+            .iter()
+            .map(|(from, to, amount)| {
+                if from == to {
+                    return Ok(*amount);
+                }
+                tables
+                    .get(from)
+                    .and_then(|table| table.data.get(to))
+                    .and_then(|rate| Some(amount * rate.value?))
+                    .ok_or_else(|| error::CurrencyapiError::CurrencyNotFound { code: to.clone() })
+            })
+            .collect()
+    }
+
+    /// Converts `(from, to, amount)` pairs labeled with an identifier of the caller's
+    /// choice using the latest rates.
+    ///
+    /// Pairs are grouped by their base currency like [Currencyapi::convert_pairs], with at
+    /// most `max_concurrency` requests in flight at once. If the request of a base fails,
+    /// its pairs are requested one by one, so a failing pair, e.g. with an unknown target
+    /// currency, does not affect the others. If an identifier appears more than once,
+    /// only one of its results is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - A slice of identifiers with the `(from, to, amount)` triple to convert.
+    /// * `max_concurrency` - The maximum number of concurrent requests, at least one is used.
+    ///
+    /// # Returns
+    ///
+    /// * `HashMap<Id, Result<f64, error::CurrencyapiError>>` - The converted amount or the error of each pair keyed by its identifier.
+    pub async fn convert_pairs_keyed<Id: Eq + Hash + Clone>(
+        &self,
+        items: &[(Id, (String, String, f64))],
+        max_concurrency: usize,
+    ) -> HashMap<Id, Result<f64, error::CurrencyapiError>> {
+        let groups = group_pairs_by_base(items.iter().map(|(_, pair)| pair));
+        let tables: HashMap<String, models::LatestResponse> =
+            futures::stream::iter(groups.into_iter().map(|(base, targets)| async move {
+                let res_body = self.latest_typed(&base, &targets.join(",")).await;
+                res_body.ok().map(|res_body| (base, res_body))
+            }))
+            .buffer_unordered(max_concurrency.max(1))
+            .filter_map(futures::future::ready)
+            .collect()
+            .await;
+        let tables = &tables;
+        run_keyed(items, max_concurrency, |(from, to, amount)| async move {
+            if from == to {
+                return Ok(*amount);
+            }
+            let rate = match tables.get(from) {
+                Some(table) => table.data.get(to).and_then(|rate| rate.value),
+                None => self
+                    .latest_typed(from, to)
+                    .await?
+                    .data
+                    .get(to)
+                    .and_then(|rate| rate.value),
+            };
+            rate.map(|rate| amount * rate)
+                .ok_or_else(|| error::CurrencyapiError::CurrencyNotFound { code: to.clone() })
+        })
+        .await
+    }
+
+    /// Converts `value` from `base_currency` into `to` with the latest rates if `date` is
+    /// `None`, today or in the future and with the historical rates of `date` otherwise.
+    ///
+    /// The conversion itself happens locally with the rate of the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the currency code of `value`.
+    /// * `to` - A string slice that holds the target currency code.
+    /// * `value` - The amount to convert.
+    /// * `date` - The date of the transaction or `None` for today.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<models::DatedConversion, error::CurrencyapiError>` - A result containing either the converted value with its effective date or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails, if the response cannot be parsed
+    /// or if `to` is missing from the response.
+    #[cfg(feature = "chrono")]
+    pub async fn convert_smart(
+        &self,
+        base_currency: impl AsRef<str>,
+        to: impl AsRef<str>,
+        value: f64,
+        date: Option<NaiveDate>,
+    ) -> Result<models::DatedConversion, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let to = to.as_ref();
+        let today = self.today();
+        let (url, date, source) = dated_rates_url(self.base_url(), base_currency, to, today, date)?;
+        self.convert_dated(url, to, value, date, source).await
+    }
+
+    /// Converts `value` into `to` with the rates of `url`.
+    #[cfg(feature = "chrono")]
+    async fn convert_dated(
+        &self,
+        url: Url,
+        to: &str,
+        value: f64,
+        date: NaiveDate,
+        source: models::RateSource,
+    ) -> Result<models::DatedConversion, CurrencyapiError> {
+        let res_body = self.get_rates(url).await?;
+        self.record_codes(res_body.data.keys());
+        let rate = res_body
+            .data
+            .get(to)
+            .and_then(|rate| rate.value)
+            .ok_or_else(|| error::CurrencyapiError::CurrencyNotFound {
+                code: String::from(to),
+            })?;
+        Ok(models::DatedConversion {
+            value: value * rate,
+            rate,
+            date,
+            source,
+        })
+    }
+
+    /// Converts `value` from `base_currency` into `to` with the average rate of `to` over
+    /// the range from `datetime_start` to `datetime_end`, e.g. for the mid-point of a
+    /// billing period.
+    ///
+    /// The range is requested with daily accuracy. Days without a rate for `to` are
+    /// skipped, so the average is based on the present rates only. The conversion itself
+    /// happens locally with the averaged rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the currency code of `value`.
+    /// * `to` - A string slice that holds the target currency code.
+    /// * `value` - The amount to convert.
+    /// * `datetime_start` - A string slice that holds the start datetime for the range.
+    /// * `datetime_end` - A string slice that holds the end datetime for the range.
+    /// * `average` - How the rates of the range are combined, see [models::RateAverage].
+    ///
+    /// # Returns
+    ///
+    /// * `Result<models::AveragedConversion, error::CurrencyapiError>` - A result containing either the converted value with the averaged rate or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails, if the response cannot be parsed
+    /// or if the range contains no rate for `to`.
+    pub async fn convert_range_average(
+        &self,
+        base_currency: impl AsRef<str>,
+        to: impl AsRef<str>,
+        value: f64,
+        datetime_start: impl AsRef<str>,
+        datetime_end: impl AsRef<str>,
+        average: models::RateAverage,
+    ) -> Result<models::AveragedConversion, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let to = to.as_ref();
+        let datetime_start = datetime_start.as_ref();
+        let datetime_end = datetime_end.as_ref();
+        let url = range_url(
+            self.base_url(),
+            base_currency,
+            datetime_start,
+            datetime_end,
+            to,
+            models::RangeAccuracy::Day,
+        )?;
+        self.convert_averaged(url, to, value, average).await
+    }
+
+    /// Converts `value` into `to` with the average rate of the range response of `url`.
+    async fn convert_averaged(
+        &self,
+        url: Url,
+        to: &str,
+        value: f64,
+        average: models::RateAverage,
+    ) -> Result<models::AveragedConversion, CurrencyapiError> {
+        let res_body: models::RangeResponse = self.get(url).await?;
+        self.record_codes(res_body.data.values().flat_map(|rates| rates.keys()));
+        let (rate, samples) = res_body.average_rate(to, average).ok_or_else(|| {
+            error::CurrencyapiError::CurrencyNotFound {
+                code: String::from(to),
+            }
+        })?;
+        Ok(models::AveragedConversion {
+            value: value * rate,
+            rate,
+            samples,
+        })
+    }
+
+    /// Fetches the latest rates of a mixed list of currency kinds, e.g. fiat and crypto
+    /// currencies, with one request per kind and merges them into one table.
+    ///
+    /// The kind of each currency is taken from the currency metadata and sent as `type`
+    /// parameter. Currencies without a known kind are requested without it. All requests
+    /// for the rates are sent concurrently.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_currency` - A string slice that holds the base currency code.
+    /// * `codes` - A slice of the target currency codes.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<models::LatestResponse, error::CurrencyapiError>` - A result containing either the merged rates or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any request fails, if a response cannot be parsed
+    /// or if the responses contain conflicting rates.
+    pub async fn latest_mixed(
+        &self,
+        base_currency: impl AsRef<str>,
+        codes: &[&str],
+    ) -> Result<models::LatestResponse, error::CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let metadata = self.currencies(None).await?;
+        let groups = group_codes_by_type(&metadata, codes);
+        self.latest_grouped(latest_url(self.base_url(), base_currency, "")?, groups)
+            .await
+    }
+
+    /// Requests the rates of each group of currencies concurrently, adding the
+    /// `type` parameter of the group to `url`, and merges the responses.
+    async fn latest_grouped(
+        &self,
+        url: Url,
+        groups: BTreeMap<Option<models::CurrencyType>, Vec<String>>,
+    ) -> Result<models::LatestResponse, CurrencyapiError> {
+        let responses =
+            futures::future::try_join_all(groups.into_iter().map(|(currency_type, codes)| {
+                let mut url = url.clone();
+                let pairs: Vec<(String, String)> = url
+                    .query_pairs()
+                    .filter(|(key, _)| key != "currencies" && key != "type")
+                    .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                    .collect();
+                let mut query = url.query_pairs_mut();
+                query
+                    .clear()
+                    .extend_pairs(pairs)
+                    .append_pair("currencies", &codes.join(","));
+                if let Some(currency_type) = currency_type {
+                    query.append_pair("type", currency_type.as_ref());
+                }
+                drop(query);
+                self.get_rates(url)
+            }))
+            .await?;
+        let merged = models::LatestResponse::merge(responses)?;
+        self.record_codes(merged.data.keys());
+        Ok(merged)
+    }
+
+    /// Computes the combined value of `holdings` in the `target` currency using the latest rates.
+    ///
+    /// All rates are fetched in a single `latest` request with `target` as base currency.
+    /// Holdings already denominated in `target` are added as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `holdings` - A slice of `(amount, currency)` pairs.
+    /// * `target` - A string slice that holds the currency code of the result.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<f64, error::CurrencyapiError>` - A result containing either the total value in the target currency or a currency API error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails, if the response cannot be parsed
+    /// or, as [CurrencyapiError::UnconvertibleCurrencies], if a rate for any holding is missing.
+    pub async fn portfolio_value(
+        &self,
+        holdings: &[(f64, String)],
+        target: impl AsRef<str>,
+    ) -> Result<f64, error::CurrencyapiError> {
+        let target = target.as_ref();
+        let codes: BTreeSet<&str> = holdings
+            .iter()
+            .map(|(_, code)| code.as_str())
+            .filter(|code| *code != target)
+            .collect();
+        let table = if codes.is_empty() {
+            models::LatestResponse {
+                data: HashMap::new(),
+                meta: None,
+            }
+        } else {
+            let currencies = codes.into_iter().collect::<Vec<_>>().join(",");
+            self.latest_typed(target, &currencies).await?
+        };
+        sum_holdings(&table, holdings, target)
+    }
+
+    /// Plans [Currencyapi::trailing] without sending any request.
+    ///
+    /// One request is made per day of the window that is not before the first
+    /// historical date, regardless of the concurrency.
+    ///
+    /// # Arguments
+    ///
+    /// * `days` - The number of days of the window, including today.
+    ///
+    /// # Returns
+    ///
+    /// * `CallPlan` - The number of requests [Currencyapi::trailing] would send.
+    #[cfg(feature = "chrono")]
+    pub fn plan_trailing(&self, days: u16) -> CallPlan {
+        let today = self.today();
+        CallPlan::new(trailing_dates(today, days, self.settings.history_start).len())
+    }
+
+    /// Plans [Currencyapi::top_movers] without sending any request.
+    ///
+    /// # Returns
+    ///
+    /// * `CallPlan` - The number of requests [Currencyapi::top_movers] would send.
+    #[cfg(feature = "chrono")]
+    pub fn plan_top_movers(&self) -> CallPlan {
+        CallPlan::new(2)
+    }
+
+    /// Plans [Currencyapi::convert_pairs] without sending any request.
+    ///
+    /// One request is made per distinct base currency, pairs converting a currency
+    /// into itself need none.
+    ///
+    /// # Arguments
+    ///
+    /// * `pairs` - A slice of `(from, to, amount)` triples to convert.
+    ///
+    /// # Returns
+    ///
+    /// * `CallPlan` - The number of requests [Currencyapi::convert_pairs] would send.
+    pub fn plan_convert_pairs(&self, pairs: &[(String, String, f64)]) -> CallPlan {
+        CallPlan::new(group_pairs_by_base(pairs).len())
+    }
+
+    /// Plans [Currencyapi::convert_pairs_keyed] without sending any request.
+    ///
+    /// One request is made per distinct base currency if all of them succeed, pairs
+    /// converting a currency into itself need none.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - A slice of identifiers with the `(from, to, amount)` triple to convert.
+    ///
+    /// # Returns
+    ///
+    /// * `CallPlan` - The number of requests [Currencyapi::convert_pairs_keyed] would send.
+    pub fn plan_convert_pairs_keyed<Id>(&self, items: &[(Id, (String, String, f64))]) -> CallPlan {
+        CallPlan::new(group_pairs_by_base(items.iter().map(|(_, pair)| pair)).len())
+    }
+
+    /// Plans [Currencyapi::portfolio_value] without sending any request.
+    ///
+    /// # Arguments
+    ///
+    /// * `holdings` - A slice of `(amount, currency)` pairs.
+    /// * `target` - A string slice that holds the currency code of the result.
+    ///
+    /// # Returns
+    ///
+    /// * `CallPlan` - The number of requests [Currencyapi::portfolio_value] would send.
+    pub fn plan_portfolio_value(
+        &self,
+        holdings: &[(f64, String)],
+        target: impl AsRef<str>,
+    ) -> CallPlan {
+        let target = target.as_ref();
+        CallPlan::new(usize::from(holdings.iter().any(|(_, code)| code != target)))
+    }
+}
+
+/// Maps the unsuccessful response of a request to `url` to the most specific error.
+pub(crate) fn status_error(
+    url: &Url,
+    status: StatusCode,
+    retry_after: Option<Duration>,
+    body: String,
+) -> CurrencyapiError {
+    if status == StatusCode::PAYLOAD_TOO_LARGE || status == StatusCode::URI_TOO_LONG {
+        return error::CurrencyapiError::RequestTooLarge {
+            status: status.as_u16(),
+        };
+    }
+    if is_plan_restriction(status, &body) {
+        return error::CurrencyapiError::EndpointNotAvailableOnPlan {
+            endpoint: endpoint_name(url),
+        };
+    }
+    if let Some(base) =
+        query_value(url, "base_currency").filter(|_| is_unsupported_base(status, &body))
+    {
+        return error::CurrencyapiError::UnsupportedBaseCurrency { base };
+    }
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        message: String,
+        #[serde(default)]
+        errors: HashMap<String, Vec<String>>,
+    }
+    match serde_json::from_str::<ErrorBody>(&body) {
+        Ok(err) => error::CurrencyapiError::ApiError {
+            status: status.as_u16(),
+            message: err.message,
+            errors: err.errors,
+            retry_after,
+        },
+        Err(_) => error::CurrencyapiError::ResponseParsingError { body },
+    }
+}
+
+/// Reads the delay of a `Retry-After` header given in seconds or, with the `chrono`
+/// feature, as http date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    #[cfg(feature = "chrono")]
+    if let Some(delay) = server_offset(value, chrono::Utc::now()) {
+        return Some(delay.to_std().unwrap_or(Duration::ZERO));
+    }
+    None
+}
+
+/// Constructs the url of a latest request.
+pub(crate) fn latest_url(
+    base_url: &Url,
+    base_currency: &str,
+    currencies: &str,
+) -> Result<Url, CurrencyapiError> {
+    let mut url = Endpoint::Latest.url(base_url)?;
+    url.query_pairs_mut()
+        .append_pair("base_currency", base_currency);
+    append_currencies(&mut url, currencies);
+    Ok(url)
+}
+
+/// Constructs the url of a historical request.
+pub(crate) fn historical_url(
+    base_url: &Url,
+    base_currency: &str,
+    date: &str,
+    currencies: &str,
+) -> Result<Url, CurrencyapiError> {
+    validate_date(date)?;
+    let mut url = Endpoint::Historical.url(base_url)?;
+    url.query_pairs_mut()
+        .append_pair("base_currency", base_currency)
+        .append_pair("date", date);
+    append_currencies(&mut url, currencies);
+    Ok(url)
+}
+
+/// Constructs the url of a range request.
+pub(crate) fn range_url(
+    base_url: &Url,
+    base_currency: &str,
+    datetime_start: &str,
+    datetime_end: &str,
+    currencies: &str,
+    accuracy: models::RangeAccuracy,
+) -> Result<Url, CurrencyapiError> {
+    let mut url = Endpoint::Range.url(base_url)?;
+    url.query_pairs_mut()
+        .append_pair("base_currency", base_currency)
+        .append_pair("datetime_start", datetime_start)
+        .append_pair("datetime_end", datetime_end)
+        .append_pair("accuracy", accuracy.as_str());
+    append_currencies(&mut url, currencies);
+    Ok(url)
+}
+
+/// Constructs the url of a convert request with the already formatted `value`.
+/// The `date` parameter is omitted if `date` is empty, which converts with the latest rates.
+pub(crate) fn convert_url(
+    base_url: &Url,
+    base_currency: &str,
+    date: &str,
+    value: &str,
+    currencies: &str,
+) -> Result<Url, CurrencyapiError> {
+    let mut url = Endpoint::Convert.url(base_url)?;
+    url.query_pairs_mut()
+        .append_pair("base_currency", base_currency);
+    if !date.is_empty() {
+        validate_date(date)?;
+        url.query_pairs_mut().append_pair("date", date);
+    }
+    url.query_pairs_mut().append_pair("value", value);
+    append_currencies(&mut url, currencies);
+    Ok(url)
+}
+
+/// Checks that `date` is a valid calendar date formatted as `YYYY-MM-DD`.
+fn validate_date(date: &str) -> Result<(), CurrencyapiError> {
+    let invalid = || CurrencyapiError::InvalidDate {
+        date: String::from(date),
+    };
+    let number = |start: usize, end: usize| {
+        date.get(start..end)
+            .filter(|part| part.bytes().all(|byte| byte.is_ascii_digit()))
+            .and_then(|part| part.parse::<u32>().ok())
+    };
+    if date.len() != 10 || date.get(4..5) != Some("-") || date.get(7..8) != Some("-") {
+        return Err(invalid());
+    }
+    let (Some(year), Some(month), Some(day)) = (number(0, 4), number(5, 7), number(8, 10)) else {
+        return Err(invalid());
+    };
+    let leap_year =
+        year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap_year => 29,
+        2 => 28,
+        _ => 0,
+    };
+    if day == 0 || day > days {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+/// Joins currency codes into the comma separated list of the api, trimming whitespace
+/// and skipping empty codes.
+pub(crate) fn join_codes(codes: &[&str]) -> String {
+    codes
+        .iter()
+        .map(|code| code.trim())
+        .filter(|code| !code.is_empty())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Appends the `currencies` parameter to `url` unless the list is empty, in which case
+/// the api returns all currencies.
+fn append_currencies(url: &mut Url, currencies: &str) {
+    if !currencies.is_empty() {
+        url.query_pairs_mut().append_pair("currencies", currencies);
+    }
+}
+
+/// Appends the `type` parameter to `url` unless `currency_type` is `None`.
+pub(crate) fn append_currency_type(url: &mut Url, currency_type: Option<models::CurrencyType>) {
+    if let Some(currency_type) = currency_type {
+        url.query_pairs_mut()
+            .append_pair("type", currency_type.as_ref());
+    }
+}
+
+/// Returns the endpoint name of a request url, i.e. the last path segment.
+fn endpoint_name(url: &Url) -> String {
+    url.path_segments()
+        .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()))
+        .map(String::from)
+        .unwrap_or_default()
+}
+
+/// Splits a comma separated list of currency codes, skipping empty entries.
+fn split_codes(currencies: &str) -> Vec<String> {
+    currencies
+        .split(',')
+        .filter(|code| !code.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Maximum number of times the currencies of a rate request are split in half
+/// after the api rejected the request as too large.
+const MAX_SPLIT_DEPTH: u32 = 4;
+
+/// Splits the `currencies` parameter of `url` into two urls requesting one half each.
+/// Returns `None` if fewer than two currencies are requested.
+fn split_currencies(url: &Url) -> Option<(Url, Url)> {
+    let codes = split_codes(&query_value(url, "currencies")?);
+    if codes.len() < 2 {
+        return None;
+    }
+    let (first, second) = codes.split_at(codes.len() / 2);
+    let with_codes = |codes: &[String]| {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(key, value)| match key.as_ref() {
+                "currencies" => (key.into_owned(), codes.join(",")),
+                _ => (key.into_owned(), value.into_owned()),
+            })
+            .collect();
+        let mut url = url.clone();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+        url
+    };
+    Some((with_codes(first), with_codes(second)))
+}
+
+/// Returns the value of the first query parameter named `key`.
+fn query_value(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Checks if an error response is the api's validation error for an unsupported base currency.
+fn is_unsupported_base(status: StatusCode, body: &str) -> bool {
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        errors: HashMap<String, serde_json::Value>,
+    }
+    status == StatusCode::UNPROCESSABLE_ENTITY
+        && serde_json::from_str::<ErrorBody>(body)
+            .map(|err| err.errors.contains_key("base_currency"))
+            .unwrap_or(false)
+}
+
+/// Removes or rejects the non-finite rates of `table` according to `policy`.
+fn apply_non_finite_rates(
+    table: &mut models::LatestResponse,
+    policy: NonFiniteRates,
+) -> Result<(), CurrencyapiError> {
+    match policy {
+        NonFiniteRates::Keep => {}
+        NonFiniteRates::Skip => table
+            .data
+            .retain(|_, rate| rate.value.is_some_and(f64::is_finite)),
+        NonFiniteRates::Reject => {
+            let invalid = table
+                .data
+                .iter()
+                .filter(|(_, rate)| !rate.value.is_some_and(f64::is_finite))
+                .map(|(code, rate)| (code.as_str(), rate.value.is_none()))
+                .min();
+            if let Some((code, missing)) = invalid {
+                let problem = if missing {
+                    "missing"
+                } else {
+                    "not a finite number"
+                };
+                return Err(CurrencyapiError::InvalidResponseData {
+                    reason: format!("rate of {} is {}", code, problem),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the base currency of a response if it differs from `requested`, preferring the
+/// base reported in the response meta over the currency whose value in `rates` equals
+/// `unit`, i.e. `1.0` for rate tables and the converted amount for conversions. Returns
+/// `None` if the base matches or cannot be checked because `requested` is missing.
+fn unexpected_base<'r>(
+    rates: impl IntoIterator<Item = (&'r String, Option<f64>)>,
+    reported: Option<&str>,
+    requested: &str,
+    unit: f64,
+) -> Option<String> {
+    if let Some(reported) = reported {
+        return (reported != requested).then(|| String::from(reported));
+    }
+    let tolerance = f64::EPSILON * unit.abs().max(1.0);
+    let is_unit = |value: f64| (value - unit).abs() <= tolerance;
+    let mut requested_value = None;
+    let mut got = None;
+    for (code, value) in rates {
+        if code == requested {
+            requested_value = Some(value);
+        } else if got.is_none() && value.is_some_and(is_unit) {
+            got = Some(code);
+        }
+    }
+    if requested_value?.is_some_and(is_unit) {
+        return None;
+    }
+    Some(got.map_or_else(|| String::from("unknown"), String::clone))
+}
+
+/// Message of the api's 403 response for endpoints not included in the current
+/// subscription plan.
+const PLAN_RESTRICTION_MESSAGE: &str =
+    "You are not allowed to use this endpoint, please upgrade your plan";
+
+/// Checks if a response is the api's error for endpoints not included in the current
+/// subscription plan, i.e. a 403 with [PLAN_RESTRICTION_MESSAGE].
+fn is_plan_restriction(status: StatusCode, body: &str) -> bool {
+    #[derive(Deserialize)]
+    struct ErrorBody {
+        message: String,
+    }
+    status == StatusCode::FORBIDDEN
+        && serde_json::from_str::<ErrorBody>(body).is_ok_and(|err| {
+            err.message
+                .trim()
+                .trim_end_matches('.')
+                .eq_ignore_ascii_case(PLAN_RESTRICTION_MESSAGE)
+        })
+}
+
+/// Converts minor units between currencies with the given number of decimal digits.
+fn convert_minor(value_minor: i64, from_digits: i32, to_digits: i32, rate: f64) -> i64 {
+    let major = value_minor as f64 / 10f64.powi(from_digits);
+    let converted = major * rate * 10f64.powi(to_digits);
+    RoundingMode::HalfEven.round(converted, 0) as i64
+}
+
+/// First day for which the api provides historical rates.
+#[cfg(feature = "chrono")]
+pub(crate) const FIRST_HISTORICAL_DATE: NaiveDate = match NaiveDate::from_ymd_opt(1999, 1, 1) {
+    Some(date) => date,
+    None => panic!("invalid first historical date"),
+};
+
+/// Constructs the url of the rates a conversion on `date` is based on, using the
+/// latest rates for today, future dates and `None`, together with the effective date.
+#[cfg(feature = "chrono")]
+fn dated_rates_url(
+    base_url: &Url,
+    base_currency: &str,
+    currencies: &str,
+    today: NaiveDate,
+    date: Option<NaiveDate>,
+) -> Result<(Url, NaiveDate, models::RateSource), CurrencyapiError> {
+    match date.filter(|date| *date < today) {
+        Some(date) => {
+            let date_param = date.format("%Y-%m-%d").to_string();
+            let url = historical_url(base_url, base_currency, &date_param, currencies)?;
+            Ok((url, date, models::RateSource::Historical))
+        }
+        None => Ok((
+            latest_url(base_url, base_currency, currencies)?,
+            today,
+            models::RateSource::Latest,
+        )),
+    }
+}
+
+/// Returns the day of `now` moved back by `tolerance`, so that dates within the
+/// tolerance before the current day still count as today.
+#[cfg(feature = "chrono")]
+fn effective_today(now: chrono::DateTime<chrono::Utc>, tolerance: Duration) -> NaiveDate {
+    let tolerance = chrono::TimeDelta::from_std(tolerance).unwrap_or(chrono::TimeDelta::MAX);
+    now.checked_sub_signed(tolerance)
+        .unwrap_or(chrono::DateTime::<chrono::Utc>::MIN_UTC)
+        .date_naive()
+}
+
+/// Returns the offset of the clock that produced the http `date` from the local time `now`.
+#[cfg(feature = "chrono")]
+fn server_offset(date: &str, now: chrono::DateTime<chrono::Utc>) -> Option<chrono::TimeDelta> {
+    let server_now = chrono::DateTime::parse_from_rfc2822(date).ok()?;
+    Some(server_now.to_utc() - now)
+}
+
+/// Returns the `days` dates ending with `today`, oldest first, without dates
+/// before `history_start`.
+#[cfg(feature = "chrono")]
+fn trailing_dates(today: NaiveDate, days: u16, history_start: NaiveDate) -> Vec<NaiveDate> {
+    (0..i64::from(days))
+        .rev()
+        .filter_map(|offset| today.checked_sub_signed(chrono::TimeDelta::days(offset)))
+        .filter(|date| *date >= history_start)
+        .collect()
+}
+
+/// Returns the date `lookback` before `today`, moved back to the preceding Friday if it
+/// falls on a weekend and not before `history_start`.
+#[cfg(feature = "chrono")]
+fn lookback_date(
+    today: NaiveDate,
+    lookback: std::time::Duration,
+    history_start: NaiveDate,
+) -> NaiveDate {
+    use chrono::{Datelike, Weekday};
+
+    let days = i64::try_from(lookback.as_secs() / 86_400).unwrap_or(i64::MAX);
+    let date = chrono::TimeDelta::try_days(days)
+        .and_then(|days| today.checked_sub_signed(days))
+        .unwrap_or(history_start);
+    let weekend_days = match date.weekday() {
+        Weekday::Sat => 1,
+        Weekday::Sun => 2,
+        _ => 0,
+    };
+    (date - chrono::TimeDelta::days(weekend_days)).max(history_start)
+}
+
+/// Groups currency codes by their kind according to `metadata`, keeping the order
+/// of `codes` within each group and skipping duplicates.
+fn group_codes_by_type(
+    metadata: &models::CurrenciesResponse,
+    codes: &[&str],
+) -> BTreeMap<Option<models::CurrencyType>, Vec<String>> {
+    let mut groups: BTreeMap<Option<models::CurrencyType>, Vec<String>> = BTreeMap::new();
+    for code in codes {
+        let currency_type = metadata
+            .data
+            .get(*code)
+            .and_then(|currency| currency.currency_type);
+        let group = groups.entry(currency_type).or_default();
+        if !group.iter().any(|existing| existing == code) {
+            group.push(String::from(*code));
+        }
+    }
+    groups
+}
+
+/// Groups conversion pairs by base currency, collecting the distinct target currencies
+/// needed for each base. Pairs converting a currency into itself need no request.
+fn group_pairs_by_base<'p>(
+    pairs: impl IntoIterator<Item = &'p (String, String, f64)>,
+) -> BTreeMap<String, Vec<String>> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (from, to, _) in pairs.into_iter().filter(|(from, to, _)| from != to) {
+        let targets = groups.entry(from.clone()).or_default();
+        if !targets.contains(to) {
+            targets.push(to.clone());
+        }
+    }
+    groups
+}
+
+/// Runs `task` for the parameters of each item with at most `max_concurrency` tasks
+/// in flight and collects the results by the identifier of the item.
+async fn run_keyed<'p, Id, P, T, F, Fut>(
+    items: &'p [(Id, P)],
+    max_concurrency: usize,
+    task: F,
+) -> HashMap<Id, Result<T, CurrencyapiError>>
+where
+    Id: Eq + Hash + Clone,
+    F: Fn(&'p P) -> Fut,
+    Fut: Future<Output = Result<T, CurrencyapiError>>,
+{
+    futures::stream::iter(items.iter().map(|(id, params)| {
+        let result = task(params);
+        async move { (id.clone(), result.await) }
+    }))
+    .buffer_unordered(max_concurrency.max(1))
+    .collect()
+    .await
+}
+
+/// Sums holdings converted into `target` using a table of rates with `target` as base currency.
+fn sum_holdings(
+    table: &models::LatestResponse,
+    holdings: &[(f64, String)],
+    target: &str,
+) -> Result<f64, CurrencyapiError> {
+    let mut total = 0.0;
+    let mut missing: Vec<String> = Vec::new();
+    for (amount, code) in holdings {
+        if code == target {
+            total += amount;
+            continue;
+        }
+        match table.data.get(code).and_then(|rate| rate.value) {
+            Some(rate) if rate != 0.0 => total += amount / rate,
+            _ => {
+                if !missing.contains(code) {
+                    missing.push(code.clone());
+                }
+            }
+        }
+    }
+    if missing.is_empty() {
+        Ok(total)
+    } else {
+        Err(CurrencyapiError::UnconvertibleCurrencies { codes: missing })
+    }
+}
+
+#[cfg(test)]
+mod api_test {
+    use super::*;
+    use crate::utils::baseline::{versioned_base_url, DEFAULT_API_VERSION};
+
+    /// Returns the url of the currencyapi the endpoint paths are appended to by default.
+    fn base() -> Url {
+        versioned_base_url(DEFAULT_API_VERSION).unwrap()
+    }
+
+    fn pair(from: &str, to: &str, amount: f64) -> (String, String, f64) {
+        (String::from(from), String::from(to), amount)
+    }
+
+    #[tokio::test]
+    async fn should_group_pairs_sharing_a_base_into_one_request() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let pairs = vec![
+            pair("USD", "EUR", 10.0),
+            pair("EUR", "GBP", 5.0),
+            pair("USD", "JPY", 1.0),
+            pair("USD", "EUR", 20.0),
+            pair("EUR", "USD", 2.0),
+            pair("GBP", "GBP", 3.0),
+        ];
+        let groups = group_pairs_by_base(&pairs);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups["USD"], vec!["EUR", "JPY"]);
+        assert_eq!(groups["EUR"], vec!["GBP", "USD"]);
+
+        let requests = Arc::new(Mutex::new(HashMap::<String, usize>::new()));
+        let counter = Arc::clone(&requests);
+        let server = MockServer::start(move |target| {
+            let url = Url::parse(&format!("http://localhost{}", target)).unwrap();
+            let base = query_value(&url, "base_currency").unwrap();
+            *counter.lock().unwrap().entry(base.clone()).or_default() += 1;
+            let body = match base.as_str() {
+                "USD" => {
+                    r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9},"JPY":{"code":"JPY","value":150.0}}}"#
+                }
+                _ => {
+                    r#"{"meta":null,"data":{"GBP":{"code":"GBP","value":0.85},"USD":{"code":"USD","value":1.1}}}"#
+                }
+            };
+            MockResponse::json(200, body)
+        });
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let amounts = api.convert_pairs(&pairs, 4).await.unwrap();
+        assert_eq!(amounts, vec![9.0, 4.25, 150.0, 18.0, 2.2, 3.0]);
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests["USD"], 1);
+        assert_eq!(requests["EUR"], 1);
+    }
+
+    #[tokio::test]
+    async fn should_key_batch_results_by_caller_id() {
+        let items = [
+            ("invoice-1", ("USD", 2.0)),
+            ("invoice-2", ("XYZ", 1.0)),
+            ("invoice-3", ("EUR", 3.0)),
+        ];
+        let rates = models::latest::latest_test::table(&[("USD", 1.25), ("EUR", 1.0)]);
+        let results = run_keyed(&items, 2, |(code, amount)| {
+            let rate = rates.data.get(*code).and_then(|rate| rate.value);
+            async move {
+                rate.map(|rate| amount / rate)
+                    .ok_or_else(|| CurrencyapiError::CurrencyNotFound {
+                        code: code.to_string(),
+                    })
+            }
+        })
+        .await;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results["invoice-1"].as_ref().unwrap(), &1.6);
+        assert_eq!(results["invoice-3"].as_ref().unwrap(), &3.0);
+        assert!(matches!(
+            &results["invoice-2"],
+            Err(CurrencyapiError::CurrencyNotFound { code }) if code == "XYZ"
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_group_keyed_pairs_by_base_and_isolate_failures() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&targets);
+        let server = MockServer::start(move |target| {
+            recorded.lock().unwrap().push(target.to_string());
+            match target {
+                "/latest?base_currency=USD&currencies=EUR%2CGBP" => MockResponse::json(
+                    200,
+                    r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9},"GBP":{"code":"GBP","value":0.8}}}"#,
+                ),
+                "/latest?base_currency=EUR&currencies=USD" => MockResponse::json(
+                    200,
+                    r#"{"meta":null,"data":{"USD":{"code":"USD","value":1.1}}}"#,
+                ),
+                "/latest?base_currency=GBP&currencies=USD" => MockResponse::json(
+                    200,
+                    r#"{"meta":null,"data":{"USD":{"code":"USD","value":1.25}}}"#,
+                ),
+                _ => MockResponse::json(422, r#"{"message":"Validation error"}"#),
+            }
+        });
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let items = [
+            ("a", pair("USD", "EUR", 10.0)),
+            ("b", pair("USD", "GBP", 10.0)),
+            ("c", pair("EUR", "USD", 10.0)),
+            ("d", pair("GBP", "JPY", 10.0)),
+            ("e", pair("GBP", "USD", 10.0)),
+            ("f", pair("USD", "USD", 10.0)),
+        ];
+        let results = api.convert_pairs_keyed(&items, 2).await;
+        assert_eq!(results.len(), 6);
+        assert_eq!(results["a"].as_ref().unwrap(), &9.0);
+        assert_eq!(results["b"].as_ref().unwrap(), &8.0);
+        assert_eq!(results["c"].as_ref().unwrap(), &11.0);
+        assert!(results["d"].is_err());
+        assert_eq!(results["e"].as_ref().unwrap(), &12.5);
+        assert_eq!(results["f"].as_ref().unwrap(), &10.0);
+        let mut targets = targets.lock().unwrap().clone();
+        targets.sort();
+        assert_eq!(
+            targets,
+            [
+                "/latest?base_currency=EUR&currencies=USD",
+                "/latest?base_currency=GBP&currencies=JPY",
+                "/latest?base_currency=GBP&currencies=JPY%2CUSD",
+                "/latest?base_currency=GBP&currencies=USD",
+                "/latest?base_currency=USD&currencies=EUR%2CGBP",
+            ]
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn should_key_historical_batch_by_caller_id() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            if target.contains("date=2024-01-02") {
+                MockResponse::json(
+                    200,
+                    r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9}}}"#,
+                )
+            } else {
+                MockResponse::json(422, r#"{"message":"Validation error"}"#)
+            }
+        });
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let day = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        let items = [("opening", day(2)), ("closing", day(3))];
+        let results = api.historical_batch_keyed("USD", &["EUR"], &items, 2).await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results["opening"].as_ref().unwrap().data["EUR"].value,
+            Some(0.9)
+        );
+        assert!(results["closing"].is_err());
+    }
+
+    const MIXED_METADATA: &str = r#"{"data":{
+        "EUR":{"symbol":"€","name":"Euro","symbol_native":"€","decimal_digits":2,"code":"EUR","type":"fiat"},
+        "BTC":{"symbol":"BTC","name":"Bitcoin","symbol_native":"BTC","decimal_digits":8,"code":"BTC","type":"crypto"},
+        "GBP":{"symbol":"£","name":"British Pound","symbol_native":"£","decimal_digits":2,"code":"GBP","type":"fiat"}
+    }}"#;
+
+    #[test]
+    fn should_group_codes_by_currency_type() {
+        let metadata: models::CurrenciesResponse = serde_json::from_str(MIXED_METADATA).unwrap();
+        let groups = group_codes_by_type(&metadata, &["EUR", "BTC", "XYZ", "GBP", "EUR"]);
+        let fiat = Some(models::CurrencyType::Fiat);
+        let crypto = Some(models::CurrencyType::Crypto);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[&fiat], ["EUR", "GBP"]);
+        assert_eq!(groups[&crypto], ["BTC"]);
+        assert_eq!(groups[&None], ["XYZ"]);
+    }
+
+    #[tokio::test]
+    async fn should_merge_fiat_and_crypto_requests() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&targets);
+        let server = MockServer::start(move |target| {
+            recorded.lock().unwrap().push(String::from(target));
+            if target.contains("type=crypto") {
+                MockResponse::json(
+                    200,
+                    r#"{"data":{"BTC":{"code":"BTC","value":0.000016}},"meta":null}"#,
+                )
+            } else {
+                MockResponse::json(
+                    200,
+                    r#"{"data":{"EUR":{"code":"EUR","value":0.92},"GBP":{"code":"GBP","value":0.79}},"meta":null}"#,
+                )
+            }
+        });
+        let metadata: models::CurrenciesResponse = serde_json::from_str(MIXED_METADATA).unwrap();
+        let api = Currencyapi::new("key").unwrap();
+        let res = api
+            .latest_grouped(
+                server.path("latest?base_currency=USD&currencies="),
+                group_codes_by_type(&metadata, &["EUR", "BTC", "GBP"]),
+            )
+            .await
+            .unwrap();
+
+        let mut targets = targets.lock().unwrap().clone();
+        targets.sort();
+        assert_eq!(
+            targets,
+            [
+                "/latest?base_currency=USD&currencies=BTC&type=crypto",
+                "/latest?base_currency=USD&currencies=EUR%2CGBP&type=fiat",
+            ]
+        );
+        assert_eq!(
+            res.values_for(&["EUR", "BTC", "GBP"]),
+            [Some(0.92), Some(0.000016), Some(0.79)]
+        );
+    }
+
+    fn holding(amount: f64, code: &str) -> (f64, String) {
+        (amount, String::from(code))
+    }
+
+    #[test]
+    fn should_sum_mixed_currency_portfolio() {
+        let table = models::latest::latest_test::table(&[("USD", 1.25), ("JPY", 160.0)]);
+        let holdings = [
+            holding(100.0, "EUR"),
+            holding(50.0, "USD"),
+            holding(8000.0, "JPY"),
+            holding(25.0, "USD"),
+        ];
+        let total = sum_holdings(&table, &holdings, "EUR").unwrap();
+        assert!((total - 210.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn should_report_every_unconvertible_currency() {
+        let table = models::latest::latest_test::table(&[("USD", 1.25)]);
+        let holdings = [
+            holding(1.0, "XYZ"),
+            holding(50.0, "USD"),
+            holding(2.0, "ABC"),
+            holding(3.0, "XYZ"),
+        ];
+        let err = sum_holdings(&table, &holdings, "EUR").unwrap_err();
+        assert!(matches!(
+            err,
+            CurrencyapiError::UnconvertibleCurrencies { codes } if codes == ["XYZ", "ABC"]
+        ));
+    }
+
+    #[test]
+    fn should_convert_usd_cents_to_jpy_yen() {
+        assert_eq!(convert_minor(1999, 2, 0, 149.73), 2993);
+    }
+
+    #[test]
+    fn should_convert_jpy_yen_to_usd_cents() {
+        assert_eq!(convert_minor(2993, 0, 2, 1.0 / 149.73), 1999);
+        assert_eq!(convert_minor(-500, 0, 2, 0.0067), -335);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn should_fetch_five_day_trailing_window() {
+        use crate::testing::{MockResponse, MockServer};
+
+        use chrono::Datelike;
+
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&targets);
+        let server = MockServer::start(move |target| {
+            recorded.lock().unwrap().push(String::from(target));
+            let url = Url::parse("http://localhost")
+                .unwrap()
+                .join(target)
+                .unwrap();
+            let day = query_value(&url, "date").map_or(0, |date| {
+                NaiveDate::parse_from_str(&date, "%Y-%m-%d").unwrap().day()
+            });
+            MockResponse::json(
+                200,
+                &format!(
+                    r#"{{"meta":null,"data":{{"EUR":{{"code":"EUR","value":{}}}}}}}"#,
+                    day
+                ),
+            )
+        });
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let today = api.today();
+        let res = api.trailing("USD", &["EUR"], 5).await.unwrap();
+
+        let expected: Vec<NaiveDate> = (0..5)
+            .rev()
+            .map(|offset| today - chrono::TimeDelta::days(offset))
+            .collect();
+        assert_eq!(res.keys().copied().collect::<Vec<_>>(), expected);
+        assert_eq!(res[&today]["EUR"], 0.0);
+        for date in &expected[..4] {
+            assert_eq!(res[date]["EUR"], f64::from(date.day()));
+        }
+        let mut targets = targets.lock().unwrap().clone();
+        targets.sort();
+        let mut sent: Vec<String> = expected[..4]
+            .iter()
+            .map(|date| format!("/historical?base_currency=USD&date={}&currencies=EUR", date))
+            .collect();
+        sent.push(String::from("/latest?base_currency=USD&currencies=EUR"));
+        sent.sort();
+        assert_eq!(targets, sent);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn should_rank_top_movers_against_friday_for_weekend_lookback() {
+        use crate::testing::{MockResponse, MockServer};
+
+        use chrono::Datelike;
+
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&targets);
+        let server = MockServer::start(move |target| {
+            recorded.lock().unwrap().push(String::from(target));
+            let body = if target.starts_with("/historical") {
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":1.0},"GBP":{"code":"GBP","value":0.8},"JPY":{"code":"JPY","value":150.0}}}"#
+            } else {
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":1.1},"GBP":{"code":"GBP","value":0.6},"JPY":{"code":"JPY","value":151.0}}}"#
+            };
+            MockResponse::json(200, body)
+        });
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let today = api.today();
+        let days_since_saturday = match (today.weekday().num_days_from_monday() + 2) % 7 {
+            0 => 7,
+            days => days,
+        };
+        let lookback = std::time::Duration::from_secs(u64::from(days_since_saturday) * 86_400);
+        let friday = today - chrono::TimeDelta::days(i64::from(days_since_saturday) + 1);
+        assert_eq!(friday.weekday(), chrono::Weekday::Fri);
+
+        let movers = api
+            .top_movers("USD", &["EUR", "GBP", "JPY"], lookback, 2)
+            .await
+            .unwrap();
+        let ranking: Vec<(&str, f64, f64)> = movers
+            .iter()
+            .map(|mover| (mover.code.as_str(), mover.from, mover.to))
+            .collect();
+        assert_eq!(ranking, [("GBP", 0.8, 0.6), ("EUR", 1.0, 1.1)]);
+        let mut targets = targets.lock().unwrap().clone();
+        targets.sort();
+        assert_eq!(
+            targets,
+            [
+                format!(
+                    "/historical?base_currency=USD&date={}&currencies=EUR%2CGBP%2CJPY",
+                    friday
+                ),
+                String::from("/latest?base_currency=USD&currencies=EUR%2CGBP%2CJPY"),
+            ]
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_fill_forward_weekend_lookback_to_friday() {
+        let week = std::time::Duration::from_secs(7 * 86_400);
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let start = FIRST_HISTORICAL_DATE;
+        assert_eq!(
+            lookback_date(date(2024, 3, 6), week, start),
+            date(2024, 2, 28)
+        );
+        assert_eq!(
+            lookback_date(date(2024, 3, 9), week, start),
+            date(2024, 3, 1)
+        );
+        assert_eq!(
+            lookback_date(date(2024, 3, 10), week, start),
+            date(2024, 3, 1)
+        );
+        assert_eq!(
+            lookback_date(date(2024, 3, 10), std::time::Duration::MAX, start),
+            FIRST_HISTORICAL_DATE
+        );
+        assert_eq!(
+            lookback_date(date(2024, 3, 10), week, date(2024, 3, 5)),
+            date(2024, 3, 5)
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_clamp_trailing_window_to_historical_depth() {
+        let today = NaiveDate::from_ymd_opt(1999, 1, 3).unwrap();
+        assert_eq!(trailing_dates(today, 30, FIRST_HISTORICAL_DATE).len(), 3);
+        assert!(trailing_dates(today, 0, FIRST_HISTORICAL_DATE).is_empty());
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let start = NaiveDate::from_ymd_opt(2024, 3, 8).unwrap();
+        assert_eq!(trailing_dates(today, 30, start).first(), Some(&start));
+        let api = Currencyapi::builder("key")
+            .history_start(start)
+            .build()
+            .unwrap();
+        assert_eq!(api.config_summary().history_start, start);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_use_latest_rates_for_today_and_future_dates() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        for date in [None, Some(today), today.succ_opt()] {
+            let (url, effective, source) =
+                dated_rates_url(&base(), "USD", "EUR", today, date).unwrap();
+            assert_eq!(url.path(), "/v3/latest");
+            assert_eq!(effective, today);
+            assert_eq!(source, models::RateSource::Latest);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_use_historical_rates_for_past_dates() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let date = NaiveDate::from_ymd_opt(2023, 12, 29).unwrap();
+        let (url, effective, source) =
+            dated_rates_url(&base(), "USD", "EUR", today, Some(date)).unwrap();
+        assert_eq!(url.path(), "/v3/historical");
+        assert_eq!(query_value(&url, "date").as_deref(), Some("2023-12-29"));
+        assert_eq!(effective, date);
+        assert_eq!(source, models::RateSource::Historical);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_accept_previous_day_within_clock_skew_tolerance() {
+        use chrono::{TimeZone, Utc};
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 2, 0, 3, 0).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let tolerance = Duration::from_secs(5 * 60);
+        let today = effective_today(now, tolerance);
+        assert_eq!(today, date);
+        let (url, _, source) = dated_rates_url(&base(), "USD", "EUR", today, Some(date)).unwrap();
+        assert_eq!(url.path(), "/v3/latest");
+        assert_eq!(source, models::RateSource::Latest);
+        let today = effective_today(now, Duration::ZERO);
+        let (_, _, source) = dated_rates_url(&base(), "USD", "EUR", today, Some(date)).unwrap();
+        assert_eq!(source, models::RateSource::Historical);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_compute_offset_of_server_clock_from_date_header() {
+        use chrono::{TimeDelta, TimeZone, Utc};
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 23, 58, 0).unwrap();
+        let offset = server_offset("Sat, 02 Mar 2024 00:00:30 GMT", now);
+        assert_eq!(offset, Some(TimeDelta::seconds(150)));
+        assert_eq!(server_offset("yesterday", now), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn should_convert_with_rate_of_dated_response() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let body = r#"{"data":{"EUR":{"code":"EUR","value":0.9}},"meta":null}"#;
+        let server = MockServer::start(move |_| MockResponse::json(200, body));
+        let api = Currencyapi::new("key").unwrap();
+        let date = NaiveDate::from_ymd_opt(2023, 12, 29).unwrap();
+        let url = server.path("historical?base_currency=USD&date=2023-12-29&currencies=EUR");
+        let source = models::RateSource::Historical;
+        let res = api
+            .convert_dated(url.clone(), "EUR", 50.0, date, source)
+            .await
+            .unwrap();
+        assert_eq!(res.value, 45.0);
+        assert_eq!(res.rate, 0.9);
+        assert_eq!(res.date, date);
+        let err = api
+            .convert_dated(url, "GBP", 50.0, date, source)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CurrencyapiError::CurrencyNotFound { code } if code == "GBP"));
+    }
+
+    #[tokio::test]
+    async fn should_convert_with_average_rate_of_range() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let body = r#"{"data":[
+            {"datetime":"2024-01-01T23:59:59Z","currencies":{"EUR":{"code":"EUR","value":0.9}}},
+            {"datetime":"2024-01-02T23:59:59Z","currencies":{}},
+            {"datetime":"2024-01-03T23:59:59Z","currencies":{"EUR":{"code":"EUR","value":0.94}}},
+            {"datetime":"2024-01-04T23:59:59Z","currencies":{"EUR":{"code":"EUR","value":0.95}}}
+        ]}"#;
+        let server = MockServer::start(move |_| MockResponse::json(200, body));
+        let api = Currencyapi::new("key").unwrap();
+        let url = server.path("range?base_currency=USD&currencies=EUR&accuracy=day");
+        let res = api
+            .convert_averaged(url.clone(), "EUR", 300.0, models::RateAverage::Mean)
+            .await
+            .unwrap();
+        assert!((res.rate - 0.93).abs() < 1e-12);
+        assert!((res.value - 279.0).abs() < 1e-9);
+        assert_eq!(res.samples, 3);
+        let res = api
+            .convert_averaged(url.clone(), "EUR", 300.0, models::RateAverage::Median)
+            .await
+            .unwrap();
+        assert_eq!(res.rate, 0.94);
+        let err = api
+            .convert_averaged(url, "GBP", 300.0, models::RateAverage::Mean)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CurrencyapiError::CurrencyNotFound { code } if code == "GBP"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_plan_one_call_per_day_of_historical_backfill() {
+        let api = Currencyapi::new("key").unwrap();
+        assert_eq!(api.plan_trailing(90).estimated_calls, 90);
+        assert_eq!(api.plan_trailing(0).estimated_calls, 0);
+    }
+
+    #[test]
+    fn should_plan_convert_pairs_without_identity_pairs() {
+        let api = Currencyapi::new("key").unwrap();
+        let pairs = vec![
+            pair("USD", "EUR", 10.0),
+            pair("EUR", "GBP", 5.0),
+            pair("USD", "JPY", 1.0),
+            pair("GBP", "GBP", 3.0),
+        ];
+        let items: Vec<(usize, (String, String, f64))> =
+            pairs.iter().cloned().enumerate().collect();
+        assert_eq!(api.plan_convert_pairs(&pairs).estimated_calls, 2);
+        assert_eq!(api.plan_convert_pairs_keyed(&items).estimated_calls, 2);
+        assert_eq!(
+            api.plan_portfolio_value(&[holding(1.0, "USD")], "USD")
+                .estimated_calls,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn should_detect_plan_restriction_for_range() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                403,
+                r#"{"message":"You are not allowed to use this endpoint, please upgrade your plan","info":"https://app.currencyapi.com/subscription"}"#,
+            )
+        });
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let err = api
+            .range(
+                "USD",
+                "2024-01-01T00:00:00Z",
+                "2024-01-07T00:00:00Z",
+                &["EUR"],
+                models::RangeAccuracy::Day,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CurrencyapiError::EndpointNotAvailableOnPlan { endpoint } if endpoint == "range"
+        ));
+    }
+
+    #[test]
+    fn should_not_treat_other_forbidden_bodies_as_plan_restriction() {
+        let body =
+            r#"{"message":"You are not allowed to use this endpoint, please upgrade your plan"}"#;
+        assert!(is_plan_restriction(StatusCode::FORBIDDEN, body));
+        assert!(!is_plan_restriction(StatusCode::UNAUTHORIZED, body));
+        for body in [
+            r#"{"message":"Invalid authentication credentials"}"#,
+            r#"{"message":"Your monthly plan quota has been exceeded"}"#,
+            r#"{"message":"Subscription expired"}"#,
+            "Forbidden",
+        ] {
+            assert!(!is_plan_restriction(StatusCode::FORBIDDEN, body));
+        }
+    }
+
+    const HIGH_PRECISION_BODY: &str =
+        r#"{"data":{"BTC":{"code":"BTC","value":0.0000159876543210987654321}},"meta":null}"#;
+
+    #[test]
+    fn should_round_high_precision_rate_with_lossy_numbers() {
+        let api = Currencyapi::new("key").unwrap();
+        let res: models::LatestResponse =
+            api.parse_body(String::from(HIGH_PRECISION_BODY)).unwrap();
+        assert_eq!(res.data["BTC"].value, Some(0.000015987654321098765));
+    }
+
+    #[test]
+    fn should_reject_high_precision_rate_with_strict_numbers() {
+        let api = Currencyapi::builder("key")
+            .number_handling(NumberHandling::Strict)
+            .build()
+            .unwrap();
+        let err = api
+            .parse_body::<models::LatestResponse>(String::from(HIGH_PRECISION_BODY))
+            .unwrap_err();
+        assert!(matches!(err, CurrencyapiError::PrecisionLoss { .. }));
+        let exact = r#"{"data":{"EUR":{"code":"EUR","value":0.921875}},"meta":null}"#;
+        let res: models::LatestResponse = api.parse_body(String::from(exact)).unwrap();
+        assert_eq!(res.data["EUR"].value, Some(0.921875));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[tokio::test]
+    async fn should_preserve_high_precision_rate_with_strict_numbers() {
+        use crate::testing::{MockResponse, MockServer};
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+
+        let server = MockServer::start(|_| MockResponse::json(200, HIGH_PRECISION_BODY));
+        let exact = Decimal::from_str("0.0000159876543210987654321").unwrap();
+        let strict = Currencyapi::builder("key")
+            .base_url(server.url.as_str())
+            .number_handling(NumberHandling::Strict)
+            .build()
+            .unwrap();
+        let res = strict.latest_decimal("USD", &["BTC"]).await.unwrap();
+        assert_eq!(res.data["BTC"].value, exact);
+
+        let lossy = Currencyapi::with_base_url("key", server.url.as_str()).unwrap();
+        let res = lossy.latest_decimal("USD", &["BTC"]).await.unwrap();
+        assert_ne!(res.data["BTC"].value, exact);
+        let rounded: f64 = "0.0000159876543210987654321".parse().unwrap();
+        assert_eq!(
+            res.data["BTC"].value,
+            Decimal::from_str(&rounded.to_string()).unwrap()
+        );
+    }
+
+    #[test]
+    fn should_send_default_accept_header() {
+        let api = Currencyapi::new("key").unwrap();
+        let url = Endpoint::Status.url(&base()).unwrap();
+        let request = api.request(url).unwrap().build().unwrap();
+        assert_eq!(request.headers()[ACCEPT], "application/json");
+    }
+
+    #[test]
+    fn should_send_configured_accept_header() {
+        let api = Currencyapi::builder("key")
+            .accept("application/vnd.currencyapi.v3+json")
+            .build()
+            .unwrap();
+        let url = Endpoint::Status.url(&base()).unwrap();
+        let request = api.request(url).unwrap().build().unwrap();
+        assert_eq!(
+            request.headers()[ACCEPT],
+            "application/vnd.currencyapi.v3+json"
+        );
+    }
+
+    #[test]
+    fn should_send_normalized_aliases_when_configured() {
+        let api = Currencyapi::builder("key")
+            .currency_aliases(CurrencyAliases::default())
+            .build()
+            .unwrap();
+        let mut url = Endpoint::Latest.url(&base()).unwrap();
         url.query_pairs_mut()
-            .append_pair("base_currency", base_currency)
-            .append_pair("datetime_start", datetime_start)
-            .append_pair("datetime_end", datetime_end)
-            .append_pair("accuracy", accuracy)
-            .append_pair("currencies", currencies);
-        let res_body: models::DetailsResponse = self
-            .client
-            .get(url)
-            .header("apikey", &self.settings.api_key)
-            .send()
+            .append_pair("base_currency", "HRK")
+            .append_pair("currencies", "USD,LTL");
+        let request = api.request(url).unwrap().build().unwrap();
+        assert_eq!(
+            request.url().query(),
+            Some("base_currency=EUR&currencies=USD%2CEUR")
+        );
+    }
+
+    #[test]
+    fn should_format_value_with_full_precision_by_default() {
+        let api = Currencyapi::new("key").unwrap();
+        assert_eq!(api.format_value(1.0 / 3.0), "0.3333333333333333");
+        assert_eq!(api.format_value(25.0), "25");
+    }
+
+    #[test]
+    fn should_format_value_with_configured_precision() {
+        let api = Currencyapi::builder("key")
+            .value_precision(2)
+            .build()
+            .unwrap();
+        assert_eq!(api.format_value(1.0 / 3.0), "0.33");
+        assert_eq!(api.format_value(25.0), "25.00");
+        let url = api
+            .convert_url("USD", "2024-01-01", 1.0 / 3.0, "EUR")
+            .unwrap();
+        assert!(url
+            .query_pairs()
+            .any(|(key, value)| key == "value" && value == "0.33"));
+    }
+
+    #[test]
+    fn should_join_currency_slices_and_omit_empty_lists() {
+        assert_eq!(join_codes(&["EUR", " GBP", "", "JPY "]), "EUR,GBP,JPY");
+        let url = latest_url(&base(), "USD", &join_codes(&["EUR", "GBP"])).unwrap();
+        assert_eq!(query_value(&url, "currencies").as_deref(), Some("EUR,GBP"));
+        let url = historical_url(&base(), "USD", "2024-01-01", &join_codes(&[])).unwrap();
+        assert!(url.query_pairs().all(|(key, _)| key != "currencies"));
+    }
+
+    #[test]
+    fn should_send_convert_value_with_decimals() {
+        let api = Currencyapi::new("key").unwrap();
+        let url = api
+            .convert_url("USD", "2024-01-01", 2500.50, "EUR")
+            .unwrap();
+        assert!(url.query().unwrap().contains("value=2500.5&"));
+    }
+
+    #[test]
+    fn should_omit_empty_convert_date() {
+        let url = convert_url(&base(), "USD", "", "2", "EUR").unwrap();
+        assert_eq!(
+            url.query(),
+            Some("base_currency=USD&value=2&currencies=EUR")
+        );
+        let url = convert_url(&base(), "USD", "2024-01-02", "2", "EUR").unwrap();
+        assert_eq!(
+            url.query(),
+            Some("base_currency=USD&date=2024-01-02&value=2&currencies=EUR")
+        );
+    }
+
+    #[test]
+    fn should_reject_convert_values_above_configured_max() {
+        let api = Currencyapi::builder("key")
+            .max_convert_value(1_000_000.0)
+            .build()
+            .unwrap();
+        assert!(api.convert_url("USD", "", 1_000_000.0, "EUR").is_ok());
+        assert!(api.convert_url("USD", "", -250.0, "EUR").is_ok());
+        for value in [1_000_000.01, -5e9, f64::NAN] {
+            let err = api.convert_url("USD", "", value, "EUR").unwrap_err();
+            assert!(matches!(
+                err,
+                CurrencyapiError::ValueOutOfRange { max, .. } if max == 1_000_000.0
+            ));
+        }
+        let unlimited = Currencyapi::new("key").unwrap();
+        assert!(unlimited.convert_url("USD", "", 1e300, "EUR").is_ok());
+        assert!(Currencyapi::builder("key")
+            .max_convert_value(-1.0)
+            .build()
+            .is_err());
+    }
+
+    fn counting_hook(
+        count: &Arc<std::sync::atomic::AtomicUsize>,
+        variants: &Arc<Mutex<Vec<String>>>,
+    ) -> ErrorHook {
+        let count = count.clone();
+        let variants = variants.clone();
+        Arc::new(move |err: &CurrencyapiError| {
+            count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let variant = format!("{:?}", err);
+            let name = variant.split([' ', '{', '(']).next().unwrap_or_default();
+            variants.lock().unwrap().push(String::from(name));
+        })
+    }
+
+    #[tokio::test]
+    async fn should_call_error_hook_once_per_failed_request() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| match target {
+            "/broken" => MockResponse::json(200, "not json"),
+            "/restricted" => MockResponse::json(
+                403,
+                r#"{"message":"You are not allowed to use this endpoint, please upgrade your plan"}"#,
+            ),
+            _ => MockResponse::json(200, r#"{"data":{},"meta":null}"#),
+        });
+        let count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let variants = Arc::new(Mutex::new(Vec::new()));
+        let api = Currencyapi::builder("key")
+            .on_error(counting_hook(&count, &variants))
+            .build()
+            .unwrap();
+
+        let ok = api.get::<models::DetailsResponse>(server.path("ok")).await;
+        assert!(ok.is_ok());
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        let _ = api
+            .get::<models::DetailsResponse>(server.path("broken"))
+            .await;
+        let _ = api
+            .get::<models::DetailsResponse>(server.path("restricted"))
+            .await;
+        let refused = Url::parse("http://127.0.0.1:1/latest").unwrap();
+        let _ = api.get::<models::DetailsResponse>(refused).await;
+
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert_eq!(
+            *variants.lock().unwrap(),
+            vec![
+                "ResponseParsingError",
+                "EndpointNotAvailableOnPlan",
+                "ConnectError"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_report_health_of_status_endpoint() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| match target {
+            "/healthy" => MockResponse::json(
+                200,
+                r#"{"account_id":1,"quotas":{"month":{"total":300,"used":72,"remaining":228}}}"#,
+            ),
+            "/exhausted" => MockResponse::json(429, r#"{"message":"quota exceeded"}"#),
+            _ => MockResponse::json(401, r#"{"message":"Invalid authentication credentials"}"#),
+        });
+        let api = Currencyapi::new("key").unwrap();
+        let health = |reachable, authorized, quota_remaining| Health {
+            reachable,
+            authorized,
+            quota_remaining,
+        };
+        assert_eq!(
+            api.health_at(server.path("healthy")).await,
+            health(true, true, Some(228))
+        );
+        assert_eq!(
+            api.health_at(server.path("exhausted")).await,
+            health(true, true, Some(0))
+        );
+        assert_eq!(
+            api.health_at(server.path("unauthorized")).await,
+            health(true, false, None)
+        );
+        let refused = Url::parse("http://127.0.0.1:1/status").unwrap();
+        assert_eq!(api.health_at(refused).await, health(false, false, None));
+    }
+
+    #[tokio::test]
+    async fn should_send_requests_to_configured_base_url() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            let status = if target.starts_with("/mock/v3/latest?") {
+                200
+            } else {
+                404
+            };
+            MockResponse::json(
+                status,
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9}}}"#,
+            )
+        });
+        let base_url = server.path("mock/v3/");
+        let api = Currencyapi::with_base_url("key", base_url.as_str()).unwrap();
+        let res = api.latest("USD", &["EUR"], None).await.unwrap();
+        assert_eq!(res.data["EUR"].value, Some(0.9));
+        assert_eq!(api.config_summary().base_url, base_url.as_str());
+        assert!(matches!(
+            Currencyapi::with_base_url("key", "localhost:8080"),
+            Err(CurrencyapiError::UrlConstruction)
+        ));
+    }
+
+    #[test]
+    fn should_build_base_url_from_api_version() {
+        let summary = Currencyapi::new("key").unwrap().config_summary();
+        assert_eq!(summary.base_url, "https://api.currencyapi.com/v3/");
+        assert_eq!(summary.api_version.as_deref(), Some("v3"));
+        let summary = Currencyapi::with_version("key", "v4")
+            .unwrap()
+            .config_summary();
+        assert_eq!(summary.base_url, "https://api.currencyapi.com/v4/");
+        assert_eq!(summary.api_version.as_deref(), Some("v4"));
+        let summary = Currencyapi::builder("key")
+            .api_version("v4")
+            .base_url("http://localhost:8080/v3/")
+            .build()
+            .unwrap()
+            .config_summary();
+        assert_eq!(summary.base_url, "http://localhost:8080/v3/");
+        assert_eq!(summary.api_version, None);
+        assert!(matches!(
+            Currencyapi::with_version("key", "v4/"),
+            Err(CurrencyapiError::InvalidConfiguration { .. })
+        ));
+    }
+
+    #[test]
+    fn should_read_retry_after_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn should_validate_dates() {
+        for date in ["2024-01-31", "2024-02-29", "2000-02-29", "1999-12-01"] {
+            assert!(validate_date(date).is_ok(), "{}", date);
+        }
+        for date in [
+            "2024-13-40",
+            "2023-02-29",
+            "1900-02-29",
+            "2024-04-31",
+            "2024-00-10",
+            "2024-01-00",
+            "2024-1-01",
+            "2024/01/01",
+            "20240101",
+            "+024-01-01",
+            "",
+        ] {
+            assert!(
+                matches!(
+                    validate_date(date),
+                    Err(CurrencyapiError::InvalidDate { .. })
+                ),
+                "{}",
+                date
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_dates_without_request() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&targets);
+        let server = MockServer::start(move |target| {
+            recorded.lock().unwrap().push(String::from(target));
+            MockResponse::json(
+                200,
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9}}}"#,
+            )
+        });
+        let api = Currencyapi::with_base_url("key", server.url.as_str()).unwrap();
+        let err = api.historical("USD", "2024-13-40", &["EUR"]).await;
+        assert!(matches!(err, Err(CurrencyapiError::InvalidDate { date }) if date == "2024-13-40"));
+        let err = api.convert("USD", "yesterday", 1.0, &["EUR"]).await;
+        assert!(matches!(err, Err(CurrencyapiError::InvalidDate { .. })));
+        assert!(targets.lock().unwrap().is_empty());
+
+        #[cfg(feature = "chrono")]
+        {
+            let date = NaiveDate::from_ymd_opt(2024, 2, 9).unwrap();
+            api.historical_on("USD", date, &["EUR"]).await.unwrap();
+            api.convert_on("USD", date, 2.0, &["EUR"]).await.unwrap();
+            assert_eq!(
+                *targets.lock().unwrap(),
+                [
+                    "/historical?base_currency=USD&date=2024-02-09&currencies=EUR",
+                    "/convert?base_currency=USD&date=2024-02-09&value=2&currencies=EUR",
+                ]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn should_map_error_responses_to_api_error() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| match target {
+            "/unauthorized" => {
+                MockResponse::json(401, r#"{"message":"Invalid authentication credentials"}"#)
+            }
+            "/invalid" => MockResponse::json(
+                422,
+                r#"{"message":"Validation error","errors":{"currencies":["The selected currencies is invalid."]}}"#,
+            ),
+            "/limited" => MockResponse::json(429, r#"{"message":"API rate limit exceeded"}"#),
+            _ => MockResponse::json(502, "Bad Gateway"),
+        });
+        let api = Currencyapi::new("key").unwrap();
+        let get = |path: &'static str| {
+            let api = api.clone();
+            let url = server.path(path);
+            async move { api.get::<models::DetailsResponse>(url).await.unwrap_err() }
+        };
+
+        match get("unauthorized").await {
+            CurrencyapiError::ApiError {
+                status,
+                message,
+                errors,
+                ..
+            } => {
+                assert_eq!(status, 401);
+                assert_eq!(message, "Invalid authentication credentials");
+                assert!(errors.is_empty());
+            }
+            err => panic!("unexpected error {:?}", err),
+        }
+        match get("invalid").await {
+            CurrencyapiError::ApiError { status, errors, .. } => {
+                assert_eq!(status, 422);
+                assert_eq!(
+                    errors["currencies"],
+                    ["The selected currencies is invalid."]
+                );
+            }
+            err => panic!("unexpected error {:?}", err),
+        }
+        assert!(matches!(
+            get("limited").await,
+            CurrencyapiError::ApiError { status: 429, .. }
+        ));
+        assert!(matches!(
+            get("gateway").await,
+            CurrencyapiError::ResponseParsingError { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_fail_with_timeout_for_slow_responses() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"quotas":{"month":{"total":1,"used":0,"remaining":1}}}"#,
+            )
+            .delayed(Duration::from_millis(500))
+        });
+        let api = Currencyapi::with_timeout("key", Duration::from_millis(50)).unwrap();
+        assert_eq!(
+            api.config_summary().timeout,
+            Some(Duration::from_millis(50))
+        );
+        let err = api
+            .get::<models::StatusResponse>(server.path("status"))
             .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?
-            .json()
+            .unwrap_err();
+        assert!(err.is_timeout());
+        assert!(matches!(err, CurrencyapiError::Timeout { .. }));
+
+        let refused = Url::parse("http://127.0.0.1:1/status").unwrap();
+        let err = api
+            .get::<models::StatusResponse>(refused)
             .await
-            .map_err(|err| error::CurrencyapiError::RequestError { source: err })?;
-        Ok(res_body)
+            .unwrap_err();
+        assert!(!err.is_timeout());
+        assert!(matches!(err, CurrencyapiError::ConnectError { .. }));
+    }
+
+    #[tokio::test]
+    async fn should_fail_with_read_body_error_for_truncated_responses() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/status", listener.local_addr().unwrap())).unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf);
+            let head = "HTTP/1.1 200 OK\r\nContent-Length: 100\r\nConnection: close\r\n\r\n";
+            let _ = stream.write_all(format!("{}{{\"quotas\"", head).as_bytes());
+        });
+        let api = Currencyapi::new("key").unwrap();
+        let err = api.get::<models::StatusResponse>(url).await.unwrap_err();
+        assert!(
+            matches!(err, CurrencyapiError::ReadBodyError { .. }),
+            "{:?}",
+            err
+        );
+    }
+
+    #[cfg(feature = "decimal")]
+    #[tokio::test]
+    async fn should_fetch_latest_rates_as_decimal() {
+        use crate::testing::{MockResponse, MockServer};
+        use rust_decimal::Decimal;
+
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.1},"GBP":{"code":"GBP","value":0.2}}}"#,
+            )
+        });
+        let api = Currencyapi::with_base_url("key", server.url.as_str()).unwrap();
+        let res = api.latest_decimal("USD", &["EUR", "GBP"]).await.unwrap();
+        assert_eq!(
+            res.data["EUR"].value + res.data["GBP"].value,
+            Decimal::new(3, 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_filter_by_currency_type() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&targets);
+        let server = MockServer::start(move |target| {
+            recorded.lock().unwrap().push(String::from(target));
+            if target.starts_with("/currencies") {
+                MockResponse::json(
+                    200,
+                    r#"{"data":{"BTC":{"symbol":"BTC","name":"Bitcoin","symbol_native":"BTC","decimal_digits":8,"code":"BTC","type":"crypto"}}}"#,
+                )
+            } else {
+                MockResponse::json(
+                    200,
+                    r#"{"meta":null,"data":{"BTC":{"code":"BTC","value":0.000016}}}"#,
+                )
+            }
+        });
+        let api = Currencyapi::with_base_url("key", server.url.as_str()).unwrap();
+        let crypto = Some(models::CurrencyType::Crypto);
+
+        let res = api.currencies(crypto).await.unwrap();
+        assert_eq!(res.data["BTC"].currency_type, crypto);
+        api.latest("USD", &[], crypto).await.unwrap();
+        api.latest("USD", &["BTC"], None).await.unwrap();
+        assert_eq!(
+            *targets.lock().unwrap(),
+            [
+                "/currencies?type=crypto",
+                "/latest?base_currency=USD&type=crypto",
+                "/latest?base_currency=USD&currencies=BTC",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_fetch_app_state_with_partial_failures() {
+        use crate::testing::{MockResponse, MockServer};
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let currencies_fail = Arc::new(AtomicBool::new(false));
+        let server = MockServer::start({
+            let requests = Arc::clone(&requests);
+            let currencies_fail = Arc::clone(&currencies_fail);
+            move |target| {
+                requests.fetch_add(1, Ordering::SeqCst);
+                match target.split('?').next().unwrap_or_default() {
+                    "/status" => MockResponse::json(
+                        200,
+                        r#"{"quotas":{"month":{"total":300,"used":72,"remaining":228}}}"#,
+                    ),
+                    "/currencies" if currencies_fail.load(Ordering::SeqCst) => {
+                        MockResponse::json(500, r#"{"message":"Internal Server Error"}"#)
+                    }
+                    "/currencies" => MockResponse::json(
+                        200,
+                        r#"{"data":{"EUR":{"symbol":"€","name":"Euro","symbol_native":"€","decimal_digits":2,"rounding":0,"code":"EUR"}}}"#,
+                    ),
+                    "/latest" => MockResponse::json(
+                        200,
+                        r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9}}}"#,
+                    ),
+                    _ => MockResponse::json(404, "{}"),
+                }
+            }
+        });
+        let api = Currencyapi::with_base_url("key", server.url.as_str()).unwrap();
+
+        let state = api.init("USD", &["EUR"]).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert!(state.is_complete());
+        assert_eq!(state.status.unwrap().quotas.month.remaining, 228);
+        assert_eq!(state.currencies.unwrap().data["EUR"].name, "Euro");
+        assert_eq!(state.latest.unwrap().data["EUR"].value, Some(0.9));
+
+        currencies_fail.store(true, Ordering::SeqCst);
+        let state = api.init("USD", &["EUR"]).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 6);
+        assert!(!state.is_complete());
+        assert!(state.status.is_ok());
+        assert!(matches!(
+            state.currencies,
+            Err(CurrencyapiError::ApiError { status: 500, .. })
+        ));
+        assert_eq!(state.latest.unwrap().data["EUR"].value, Some(0.9));
+    }
+
+    #[tokio::test]
+    async fn should_send_api_key_as_header_only() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start_with_head(|target, head| {
+            let has_header = head.to_lowercase().contains("\r\napikey: secret-key\r\n");
+            let status = if has_header && !target.contains("secret-key") {
+                200
+            } else {
+                401
+            };
+            MockResponse::json(status, r#"{"data":{},"meta":null}"#)
+        });
+        let api = Currencyapi::new("secret-key").unwrap();
+        let url = server.path("latest?base_currency=USD&currencies=EUR");
+        assert!(api.get_rates(url).await.is_ok());
+        assert!(!format!("{:?}", api.client).contains("secret-key"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn should_stream_historical_rates_in_date_order() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            let day: u64 = target
+                .split("date=2024-02-")
+                .nth(1)
+                .and_then(|rest| rest.get(..2))
+                .and_then(|day| day.parse().ok())
+                .unwrap_or(0);
+            if day == 29 {
+                return MockResponse::json(500, "{}");
+            }
+            MockResponse::json(
+                200,
+                &format!(
+                    r#"{{"meta":null,"data":{{"EUR":{{"code":"EUR","value":{}}}}}}}"#,
+                    day
+                ),
+            )
+            .delayed(Duration::from_millis(30 - day))
+        });
+        let api = Currencyapi::builder("key")
+            .base_url(server.url.as_str())
+            .max_concurrency(NonZeroUsize::new(3).unwrap())
+            .build()
+            .unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2024, 2, day).unwrap();
+
+        let days: Vec<_> = api
+            .historical_stream("USD", &["EUR"], date(26), date(29))
+            .collect()
+            .await;
+        assert_eq!(days.len(), 4);
+        for (result, day) in days.iter().zip(26..) {
+            match result {
+                Ok((date, rates)) => {
+                    assert_eq!(date.to_string(), format!("2024-02-{}", day));
+                    assert_eq!(rates.data["EUR"].value, Some(f64::from(day)));
+                }
+                Err(_) => assert_eq!(day, 29),
+            }
+        }
+        let empty: Vec<_> = api
+            .historical_stream("USD", &[], date(2), date(1))
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn should_fetch_historical_batch_in_input_order() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            if target.contains("date=2024-01-02") {
+                return MockResponse::json(500, "{}");
+            }
+            let delay = if target.contains("date=2024-03-01") {
+                40
+            } else {
+                0
+            };
+            MockResponse::json(
+                200,
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9}}}"#,
+            )
+            .delayed(Duration::from_millis(delay))
+        });
+        let api = Currencyapi::with_base_url("key", server.url.as_str()).unwrap();
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let dates = [date(3, 1), date(1, 2), date(2, 1)];
+
+        let results = api.historical_batch("USD", &["EUR"], &dates, 2).await;
+        assert_eq!(
+            results.iter().map(|(date, _)| *date).collect::<Vec<_>>(),
+            dates
+        );
+        assert!(results[0].1.is_ok());
+        assert!(results[1].1.is_err());
+        assert_eq!(results[2].1.as_ref().unwrap().data["EUR"].value, Some(0.9));
+    }
+
+    #[tokio::test]
+    async fn should_expose_quota_of_latest_response() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let remaining = Arc::new(std::sync::atomic::AtomicU64::new(300));
+        let counter = Arc::clone(&remaining);
+        let server = MockServer::start(move |target| {
+            let response = MockResponse::json(200, r#"{"data":{},"meta":null}"#);
+            if target.starts_with("/plain") {
+                return response;
+            }
+            let left = counter.fetch_sub(1, std::sync::atomic::Ordering::SeqCst) - 1;
+            response
+                .header("X-RateLimit-Limit-Month", "300")
+                .header("X-RateLimit-Remaining-Month", &left.to_string())
+                .header("X-RateLimit-Remaining-Minute", "9")
+        });
+        let api = Currencyapi::new("key").unwrap();
+        assert_eq!(api.quota(), None);
+        api.get::<models::DetailsResponse>(server.path("latest"))
+            .await
+            .unwrap();
+        api.get::<models::DetailsResponse>(server.path("latest?currencies=EUR"))
+            .await
+            .unwrap();
+        api.get::<models::DetailsResponse>(server.path("plain"))
+            .await
+            .unwrap();
+        let quota = api.quota().unwrap();
+        assert_eq!(quota.limit_month, Some(300));
+        assert_eq!(quota.remaining_month, Some(298));
+        assert_eq!(quota.remaining_minute, Some(9));
+        assert_eq!(quota.limit_minute, None);
+    }
+
+    #[tokio::test]
+    async fn should_send_user_agent() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let agents = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&agents);
+        let server = MockServer::start_with_head(move |_, head| {
+            let agent = head.lines().find_map(|line| {
+                line.to_lowercase()
+                    .strip_prefix("user-agent: ")
+                    .map(String::from)
+            });
+            recorded.lock().unwrap().push(agent.unwrap_or_default());
+            MockResponse::json(200, r#"{"data":{},"meta":null}"#)
+        });
+        let url = server.path("latest?base_currency=USD&currencies=EUR");
+        let api = Currencyapi::new("key").unwrap();
+        api.get_rates(url.clone()).await.unwrap();
+        let api = Currencyapi::with_user_agent("key", "rates-cli/2.1").unwrap();
+        assert_eq!(
+            api.config_summary().user_agent.as_deref(),
+            Some("rates-cli/2.1")
+        );
+        api.get_rates(url).await.unwrap();
+        assert_eq!(
+            *agents.lock().unwrap(),
+            [
+                format!("currencyapi-rs/{}", env!("CARGO_PKG_VERSION")),
+                String::from("rates-cli/2.1"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_send_api_key_with_custom_client() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start_with_head(|_, head| {
+            let head = head.to_lowercase();
+            let status = if head.contains("\r\napikey: secret-key\r\n")
+                && head.contains("\r\nx-shared: pool\r\n")
+                && head.contains("\r\ncontent-type: application/json\r\n")
+            {
+                200
+            } else {
+                401
+            };
+            MockResponse::json(status, r#"{"data":{},"meta":null}"#)
+        });
+        let mut headers = HeaderMap::new();
+        headers.insert("x-shared", HeaderValue::from_static("pool"));
+        let client = Client::builder().default_headers(headers).build().unwrap();
+        let api = Currencyapi::with_client(client, "secret-key").unwrap();
+        assert!(api.config_summary().custom_http_client);
+        let url = server.path("latest?base_currency=USD&currencies=EUR");
+        assert!(api.get_rates(url).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_report_cache_as_source_of_repeated_request() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9}}}"#,
+            )
+        });
+        let api = Currencyapi::builder("key")
+            .cache_ttl(Duration::from_secs(60))
+            .build()
+            .unwrap();
+        let url = server.path("latest?base_currency=USD&currencies=EUR");
+        let (_, source) = api.get_rates_with_source(url.clone()).await.unwrap();
+        assert_eq!(source, DataSource::Network);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let (res, source) = api.get_rates_with_source(url).await.unwrap();
+        assert_eq!(res.data["EUR"].value, Some(0.9));
+        let DataSource::Cache { age } = source else {
+            panic!("expected a cached response, got {:?}", source);
+        };
+        assert!(age >= Duration::from_millis(20) && age < Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn should_fetch_single_rate() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            assert!(target.contains("currencies="), "{}", target);
+            MockResponse::json(
+                200,
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9}}}"#,
+            )
+        });
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let base = String::from("USD");
+        assert_eq!(api.rate(&base, "EUR").await.unwrap(), 0.9);
+        assert_eq!(api.rate(base, String::from("EUR")).await.unwrap(), 0.9);
+        let err = api.rate("USD", "GBP").await.unwrap_err();
+        assert!(matches!(
+            err,
+            CurrencyapiError::CurrencyNotFound { code } if code == "GBP"
+        ));
+        assert_eq!(api.convert_now("USD", 20.0, "EUR").await.unwrap(), 18.0);
+        let err = api.convert_now("USD", f64::NAN, "EUR").await.unwrap_err();
+        assert!(matches!(err, CurrencyapiError::InvalidAmount { value } if value.is_nan()));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn should_compute_change_between_two_dates() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            let value = match target {
+                t if t.contains("date=2024-01-01") => "0.8",
+                t if t.contains("date=2024-01-02") => "0.9",
+                _ => "0",
+            };
+            MockResponse::json(
+                200,
+                &format!(
+                    r#"{{"meta":null,"data":{{"EUR":{{"code":"EUR","value":{}}}}}}}"#,
+                    value
+                ),
+            )
+        });
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        let change = api.change("USD", "EUR", day(1), day(2)).await.unwrap();
+        assert_eq!(change.from_rate, 0.8);
+        assert_eq!(change.to_rate, 0.9);
+        assert!((change.absolute - 0.1).abs() < 1e-12);
+        assert!((change.percentage - 12.5).abs() < 1e-9);
+
+        let err = api.change("USD", "EUR", day(3), day(2)).await.unwrap_err();
+        assert!(matches!(err, CurrencyapiError::InvalidResponseData { .. }));
+        let err = api.change("USD", "GBP", day(1), day(2)).await.unwrap_err();
+        assert!(matches!(err, CurrencyapiError::CurrencyNotFound { code } if code == "GBP"));
+    }
+
+    #[cfg(feature = "validate")]
+    #[tokio::test]
+    async fn should_reject_unknown_codes_without_sending_request() {
+        use crate::testing::{MockResponse, MockServer};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let server = MockServer::start(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            MockResponse::json(200, r#"{"meta":null,"data":{}}"#)
+        });
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let err = api.latest("USD", &["EUR", "USDD"], None).await.unwrap_err();
+        assert!(matches!(err, CurrencyapiError::UnknownCurrency { code } if code == "USDD"));
+        let err = api.latest("UDS", &[], None).await.unwrap_err();
+        assert!(matches!(err, CurrencyapiError::UnknownCurrency { code } if code == "UDS"));
+        let err = api
+            .latest_stream_response("USD", &["EUR", "USDD"])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CurrencyapiError::UnknownCurrency { code } if code == "USDD"));
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+        assert!(api.latest("usd", &["EUR", "BTC"], None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_omit_base_currency_for_default_base() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            assert_eq!(target, "/latest?currencies=EUR%2CGBP");
+            MockResponse::json(
+                200,
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9},"GBP":{"code":"GBP","value":0.8}}}"#,
+            )
+        });
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let res = api
+            .latest_default_base(&["EUR", "GBP"], None)
+            .await
+            .unwrap();
+        assert_eq!(res.data["GBP"].value, Some(0.8));
+        assert_eq!(res.meta.and_then(|meta| meta.base_currency), None);
+    }
+
+    #[tokio::test]
+    async fn should_return_raw_payload_with_typed_rates() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"meta":{"last_updated_at":"2024-01-01T00:00:00Z","source":"ecb"},"data":{"EUR":{"code":"EUR","value":0.9,"trend":"up"}}}"#,
+            )
+        });
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let (typed, raw) = api.latest_raw("USD", &["EUR"]).await.unwrap();
+        assert_eq!(typed.data["EUR"].value, Some(0.9));
+        assert_eq!(raw["meta"]["source"], "ecb");
+        assert_eq!(raw["data"]["EUR"]["trend"], "up");
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"data":"#));
+        let api = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        let err = api.latest_raw("USD", &["EUR"]).await.unwrap_err();
+        assert!(matches!(err, CurrencyapiError::ResponseParsingError { .. }));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn should_trace_calls_without_api_key() {
+        use crate::testing::{FieldRecorder, MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            if target.starts_with("/status") {
+                return MockResponse::json(500, r#"{"message":"unavailable"}"#);
+            }
+            MockResponse::json(
+                200,
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9}}}"#,
+            )
+        });
+        let api = Currencyapi::with_base_url(String::from("secret-key"), &server.url).unwrap();
+        let recorder = FieldRecorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+        api.latest("USD", &["EUR"], None).await.unwrap();
+        let fields = recorder.fields();
+        assert!(
+            fields.contains(&String::from("endpoint=latest")),
+            "{:?}",
+            fields
+        );
+        assert!(
+            fields.contains(&String::from("base_currency=USD")),
+            "{:?}",
+            fields
+        );
+        assert!(
+            fields.contains(&String::from("http.status_code=200")),
+            "{:?}",
+            fields
+        );
+        assert!(fields
+            .iter()
+            .any(|field| field.starts_with("url=") && field.contains("/latest?")));
+
+        api.status().await.unwrap_err();
+        let fields = recorder.fields();
+        assert!(
+            fields.contains(&String::from("endpoint=status")),
+            "{:?}",
+            fields
+        );
+        assert!(
+            fields.contains(&String::from("http.status_code=500")),
+            "{:?}",
+            fields
+        );
+        assert!(
+            fields.iter().any(|field| field.starts_with("error=")),
+            "{:?}",
+            fields
+        );
+        assert!(fields.iter().all(|field| !field.contains("secret-key")));
+    }
+
+    #[tokio::test]
+    async fn should_refetch_after_clearing_cache() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let server = MockServer::start(move |_| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            MockResponse::json(
+                200,
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9}}}"#,
+            )
+        });
+        let api = Currencyapi::builder("key")
+            .base_url(server.url.as_str())
+            .cache_ttl(Duration::from_secs(60))
+            .build()
+            .unwrap();
+        let shared = api.clone();
+        api.latest("USD", &["EUR"], None).await.unwrap();
+        shared.latest("USD", &["EUR"], None).await.unwrap();
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+        shared.clear_cache();
+        api.latest("USD", &["EUR"], None).await.unwrap();
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_eq!(api.cache_stats().hits, 1);
+    }
+
+    #[tokio::test]
+    async fn should_record_interaction_of_latest_request() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let body = r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9}}}"#;
+        let server = MockServer::start(move |_| MockResponse::json(200, body));
+        let recording = Recording::default();
+        let api = Currencyapi::builder("secret-key")
+            .record_to(recording.clone())
+            .build()
+            .unwrap();
+        let url = server.path("latest?base_currency=USD&currencies=EUR");
+        api.get_rates(url.clone()).await.unwrap();
+
+        let recording = recording.lock().unwrap();
+        assert_eq!(recording.len(), 1);
+        let interaction = &recording[0];
+        assert_eq!(interaction.method, "GET");
+        assert_eq!(interaction.url, url.as_str());
+        assert_eq!(interaction.status, 200);
+        assert_eq!(interaction.body, body.as_bytes());
+        assert!(interaction
+            .request_headers
+            .iter()
+            .all(|(name, _)| name != "apikey"));
+        assert!(!format!("{:?}", interaction).contains("secret-key"));
+    }
+
+    #[tokio::test]
+    async fn should_return_raw_response_for_streaming() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"data":{},"meta":null}"#));
+        let api = Currencyapi::new("key").unwrap();
+        let response = api.send_raw(server.path("range")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.text().await.unwrap();
+        assert_eq!(body, r#"{"data":{},"meta":null}"#);
+    }
+
+    #[tokio::test]
+    async fn should_record_latency_of_sent_requests_but_not_cache_hits() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|_| MockResponse::json(200, r#"{"data":{},"meta":null}"#));
+        let api = Currencyapi::builder("key")
+            .cache_ttl(std::time::Duration::from_secs(60))
+            .build()
+            .unwrap();
+        assert_eq!(api.latency_stats().count, 0);
+        for _ in 0..2 {
+            let _: models::LatestResponse = api.get(server.path("latest")).await.unwrap();
+        }
+        let stats = api.latency_stats();
+        assert_eq!(stats.count, 1);
+        assert_eq!(stats.p50, stats.p99);
+    }
+
+    const DUPLICATE_BODY: &str = r#"{"data":{"BTC":{"code":"BTC","value":0.1},"BTC":{"code":"BTC","value":0.2}},"meta":null}"#;
+
+    #[test]
+    fn should_keep_last_duplicate_by_default() {
+        let api = Currencyapi::new("key").unwrap();
+        let res: models::LatestResponse = api.parse_body(String::from(DUPLICATE_BODY)).unwrap();
+        assert_eq!(res.data["BTC"].value, Some(0.2));
+    }
+
+    #[test]
+    fn should_reject_duplicate_currency_when_enabled() {
+        let api = Currencyapi::builder("key")
+            .reject_duplicate_currencies(true)
+            .build()
+            .unwrap();
+        let err = api
+            .parse_body::<models::LatestResponse>(String::from(DUPLICATE_BODY))
+            .unwrap_err();
+        assert!(matches!(err, CurrencyapiError::DuplicateCurrency { code } if code == "BTC"));
+    }
+
+    #[test]
+    fn should_read_api_key_from_environment_variable() {
+        let name = "CURRENCYAPI_RS_TEST_KEY";
+        std::env::remove_var(name);
+        let err = Currencyapi::from_env_var(name).unwrap_err();
+        assert!(matches!(err, CurrencyapiError::MissingApiKey { variable } if variable == name));
+        std::env::set_var(name, "");
+        assert!(matches!(
+            Currencyapi::from_env_var(name),
+            Err(CurrencyapiError::MissingApiKey { .. })
+        ));
+        std::env::set_var(name, "key");
+        assert!(Currencyapi::from_env_var(name).is_ok());
+        std::env::remove_var(name);
+    }
+
+    #[test]
+    fn should_convert_overridden_pairs_with_override_rate() {
+        let api = Currencyapi::new("key").unwrap();
+        let table =
+            crate::models::latest::latest_test::table(&[("USD", 1.0), ("EUR", 0.9), ("GBP", 0.8)]);
+        api.set_rate_override("USD", "EUR", 0.95).unwrap();
+        assert_eq!(api.convert_offline(&table, "USD", "EUR", 100.0), Some(95.0));
+        assert_eq!(api.convert_offline(&table, "EUR", "USD", 95.0), Some(100.0));
+        assert_eq!(api.convert_offline(&table, "USD", "GBP", 100.0), Some(80.0));
+        assert!(api.set_rate_override("USD", "GBP", f64::NAN).is_err());
+        api.clear_rate_overrides();
+        assert_eq!(api.convert_offline(&table, "USD", "EUR", 100.0), Some(90.0));
+    }
+
+    #[test]
+    fn should_normalize_rates_quoted_per_hundred_units() {
+        let api = Currencyapi::new("key").unwrap();
+        let table =
+            crate::models::latest::latest_test::table(&[("EUR", 0.9), ("JPY", 1.5), ("GBP", 0.8)]);
+        let currencies: models::CurrenciesResponse = serde_json::from_str(
+            r#"{"data":{"JPY":{"code":"JPY","name":"Yen","symbol":"¥","symbol_native":"￥","decimal_digits":0,"scale":100}}}"#,
+        )
+        .unwrap();
+        assert_eq!(api.convert_offline(&table, "EUR", "JPY", 9.0), Some(15.0));
+        api.set_quote_scales(&currencies);
+        let yen = api.convert_offline(&table, "EUR", "JPY", 9.0).unwrap();
+        assert!((yen - 1500.0).abs() < 1e-9);
+        let euro = api.convert_offline(&table, "JPY", "EUR", 1500.0).unwrap();
+        assert!((euro - 9.0).abs() < 1e-9);
+        assert_eq!(
+            api.convert_offline(&table, "EUR", "GBP", 9.0),
+            table.convert("EUR", "GBP", 9.0)
+        );
+    }
+
+    #[test]
+    fn should_summarize_builder_options_without_api_key() {
+        let api = Currencyapi::builder("secret-key-123")
+            .accept("application/vnd.currencyapi.v3+json")
+            .number_handling(NumberHandling::Strict)
+            .cache_ttl(std::time::Duration::from_secs(60))
+            .retry(RetryConfig::default())
+            .value_precision(4)
+            .track_seen_codes(true)
+            .build()
+            .unwrap();
+        let summary = api.config_summary();
+        assert_eq!(summary.base_url, "https://api.currencyapi.com/v3/");
+        assert_eq!(summary.accept, "application/vnd.currencyapi.v3+json");
+        assert_eq!(summary.number_handling, NumberHandling::Strict);
+        assert!(!summary.currency_aliases);
+        assert_eq!(summary.cache_ttl, Some(std::time::Duration::from_secs(60)));
+        assert_eq!(summary.cache_capacity, None);
+        assert_eq!(summary.retry, Some(RetryConfig::default()));
+        assert_eq!(summary.value_precision, Some(4));
+        assert!(!summary.error_hook);
+        assert!(summary.track_seen_codes);
+        assert_eq!(summary.leaky_bucket, None);
+        assert!(!format!("{:?}", summary).contains("secret-key-123"));
+    }
+
+    #[test]
+    fn should_share_leaky_bucket_between_clones() {
+        let api = Currencyapi::builder("key")
+            .leaky_bucket(1.0, 2)
+            .build()
+            .unwrap();
+        let clone = api.clone();
+        assert_eq!(api.config_summary().leaky_bucket, Some((1.0, 2)));
+        let limiter = api.limiter.as_ref().unwrap();
+        assert_eq!(limiter.reserve(), std::time::Duration::ZERO);
+        assert_eq!(
+            clone.limiter.as_ref().unwrap().reserve(),
+            std::time::Duration::ZERO
+        );
+        assert!(limiter.reserve() > std::time::Duration::ZERO);
+    }
+
+    #[test]
+    fn should_split_requested_currencies_in_half() {
+        let url = latest_url(&base(), "USD", "EUR,GBP,JPY,CHF,CAD").unwrap();
+        let (first, second) = split_currencies(&url).unwrap();
+        assert_eq!(
+            query_value(&first, "currencies").as_deref(),
+            Some("EUR,GBP")
+        );
+        assert_eq!(
+            query_value(&second, "currencies").as_deref(),
+            Some("JPY,CHF,CAD")
+        );
+        assert_eq!(
+            query_value(&second, "base_currency").as_deref(),
+            Some("USD")
+        );
+        assert!(split_currencies(&latest_url(&base(), "USD", "EUR").unwrap()).is_none());
+    }
+
+    /// Returns the currencies requested by a target received by the mock server.
+    fn requested_codes(target: &str) -> Vec<String> {
+        let url = Url::parse("http://localhost")
+            .unwrap()
+            .join(target)
+            .unwrap();
+        split_codes(&query_value(&url, "currencies").unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn should_split_currencies_rejected_as_too_large() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            let codes = requested_codes(target);
+            if codes.len() > 2 {
+                return MockResponse::json(414, "URI Too Long");
+            }
+            let rates: Vec<String> = codes
+                .iter()
+                .map(|code| format!(r#""{0}":{{"code":"{0}","value":2.0}}"#, code))
+                .collect();
+            MockResponse::json(
+                200,
+                &format!(r#"{{"data":{{{}}},"meta":null}}"#, rates.join(",")),
+            )
+        });
+        let api = Currencyapi::new("key").unwrap();
+        let res = api
+            .get_rates(server.path("latest?base_currency=USD&currencies=EUR,GBP,JPY,CHF,CAD"))
+            .await
+            .unwrap();
+        let mut codes: Vec<&String> = res.data.keys().collect();
+        codes.sort();
+        assert_eq!(codes, ["CAD", "CHF", "EUR", "GBP", "JPY"]);
+        assert_eq!(res.meta.unwrap().base_currency.as_deref(), Some("USD"));
+    }
+
+    #[tokio::test]
+    async fn should_give_up_splitting_after_max_depth() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let smallest = Arc::new(Mutex::new(usize::MAX));
+        let recorded = Arc::clone(&smallest);
+        let server = MockServer::start(move |target| {
+            let codes = requested_codes(target);
+            if !codes.iter().any(|code| code == "AED") {
+                return MockResponse::json(200, r#"{"data":{},"meta":null}"#);
+            }
+            let mut smallest = recorded.lock().unwrap();
+            *smallest = (*smallest).min(codes.len());
+            MockResponse::json(413, "Payload Too Large")
+        });
+        let codes = concat!(
+            "AED,AFN,ALL,AMD,ANG,AOA,ARS,AUD,AWG,AZN,BAM,BBD,BDT,BGN,BHD,BIF,",
+            "BMD,BND,BOB,BOV,BRL,BSD,BTN,BWP,BYN,BZD,CAD,CDF,CHE,CHF,CHW,CLF,",
+            "CLP,CNY,COP,COU,CRC,CUC,CUP,CVE,CZK,DJF,DKK,DOP,DZD,EGP,ERN,ETB,",
+            "EUR,FJD,FKP,GBP,GEL,GHS,GIP,GMD,GNF,GTQ,GYD,HKD,HNL,HTG,HUF,IDR",
+        );
+        let api = Currencyapi::new("key").unwrap();
+        let err = api
+            .get_rates(server.path(&format!("latest?currencies={}", codes)))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CurrencyapiError::RequestTooLarge { status: 413 }
+        ));
+        assert_eq!(*smallest.lock().unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn should_reject_response_with_unexpected_base_in_strict_mode() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            let body = if target.contains("base_currency=GBP") {
+                r#"{"data":{"GBP":{"code":"GBP","value":1.0}},"meta":{"last_updated_at":null,"base_currency":"USD"}}"#
+            } else {
+                r#"{"data":{"USD":{"code":"USD","value":1.0},"EUR":{"code":"EUR","value":0.92}},"meta":null}"#
+            };
+            MockResponse::json(200, body)
+        });
+        let url = server.path("latest?base_currency=EUR");
+        let lenient = Currencyapi::new("key").unwrap();
+        let res = lenient.get_rates(url.clone()).await.unwrap();
+        assert_eq!(res.meta.unwrap().base_currency.as_deref(), Some("EUR"));
+        let res = lenient
+            .get_rates(server.path("latest?base_currency=GBP"))
+            .await
+            .unwrap();
+        assert_eq!(res.meta.unwrap().base_currency.as_deref(), Some("USD"));
+
+        let strict = Currencyapi::builder("key")
+            .strict_base_currency(true)
+            .build()
+            .unwrap();
+        let err = strict.get_rates(url).await.unwrap_err();
+        assert!(matches!(
+            err,
+            CurrencyapiError::UnexpectedBaseCurrency { requested, got }
+                if requested == "EUR" && got == "USD"
+        ));
+        let err = strict
+            .get_rates(server.path("latest?base_currency=GBP"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CurrencyapiError::UnexpectedBaseCurrency { requested, got }
+                if requested == "GBP" && got == "USD"
+        ));
+        assert!(strict
+            .get_rates(server.path("latest?base_currency=USD"))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_check_base_of_historical_convert_and_range_in_strict_mode() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            let body = if target.starts_with("/convert") {
+                r#"{"meta":null,"data":{"USD":{"code":"USD","value":10.0},"EUR":{"code":"EUR","value":10.8}}}"#
+            } else if target.starts_with("/range") {
+                r#"{"data":[{"datetime":"2024-01-01T23:59:59Z","currencies":{"USD":{"code":"USD","value":1.0},"EUR":{"code":"EUR","value":1.08}}}]}"#
+            } else {
+                r#"{"meta":null,"data":{"USD":{"code":"USD","value":1.0},"EUR":{"code":"EUR","value":1.08}}}"#
+            };
+            MockResponse::json(200, body)
+        });
+        let is_unexpected = |err: CurrencyapiError| {
+            matches!(
+                err,
+                CurrencyapiError::UnexpectedBaseCurrency { requested, got }
+                    if requested == "EUR" && got == "USD"
+            )
+        };
+        let strict = Currencyapi::builder("key")
+            .base_url(server.url.as_str())
+            .strict_base_currency(true)
+            .build()
+            .unwrap();
+        let codes = ["USD", "EUR"];
+        let err = strict
+            .historical("EUR", "2024-01-01", &codes)
+            .await
+            .unwrap_err();
+        assert!(is_unexpected(err));
+        let err = strict.convert("EUR", "", 10.0, &codes).await.unwrap_err();
+        assert!(is_unexpected(err));
+        let err = strict
+            .range(
+                "EUR",
+                "2024-01-01T00:00:00Z",
+                "2024-01-02T00:00:00Z",
+                &codes,
+                models::RangeAccuracy::Day,
+            )
+            .await
+            .unwrap_err();
+        assert!(is_unexpected(err));
+        assert!(strict.historical("USD", "2024-01-01", &codes).await.is_ok());
+        assert!(strict.convert("USD", "", 10.0, &codes).await.is_ok());
+
+        let lenient = Currencyapi::with_base_url(String::from("key"), &server.url).unwrap();
+        assert!(lenient
+            .historical("EUR", "2024-01-01", &codes)
+            .await
+            .is_ok());
+        assert!(lenient.convert("EUR", "", 10.0, &codes).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn should_limit_requests_in_flight_across_clones() {
+        use crate::testing::{MockResponse, MockServer};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let (current, max) = (Arc::clone(&in_flight), Arc::clone(&max_in_flight));
+        let server = MockServer::start(move |_| {
+            let now = current.fetch_add(1, Ordering::SeqCst) + 1;
+            max.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            current.fetch_sub(1, Ordering::SeqCst);
+            MockResponse::json(200, r#"{"data":{},"meta":null}"#)
+        });
+        let api = Currencyapi::builder("key")
+            .max_concurrency(NonZeroUsize::new(2).unwrap())
+            .build()
+            .unwrap();
+        let clone = api.clone();
+        let codes = [
+            "EUR", "GBP", "JPY", "CHF", "CAD", "AUD", "NZD", "SEK", "NOK", "DKK",
+        ];
+        let requests = codes.iter().enumerate().map(|(i, code)| {
+            let client = if i % 2 == 0 { &api } else { &clone };
+            let url = server.path(&format!("latest?currencies={}", code));
+            async move { client.get::<models::LatestResponse>(url).await }
+        });
+        let results = futures::future::join_all(requests).await;
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+        assert_eq!(api.config_summary().max_concurrency.get(), 2);
+    }
+
+    #[test]
+    fn should_reject_non_positive_leaky_bucket_rate() {
+        let err = Currencyapi::builder("key")
+            .leaky_bucket(0.0, 2)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, CurrencyapiError::InvalidConfiguration { .. }));
+    }
+
+    const UNSUPPORTED_BASE_BODY: &str = r#"{"message":"Validation error","errors":{"base_currency":["The selected base currency is invalid."]}}"#;
+
+    #[tokio::test]
+    async fn should_rebase_rates_from_fallback_base() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|target| {
+            if target.contains("base_currency=USD") && target.contains("XAG") {
+                MockResponse::json(
+                    200,
+                    r#"{"meta":{"last_updated_at":"2024-01-01T23:59:59Z"},"data":{"EUR":{"code":"EUR","value":0.9},"GBP":{"code":"GBP","value":0.8},"XAG":{"code":"XAG","value":0.04}}}"#,
+                )
+            } else {
+                MockResponse::json(422, UNSUPPORTED_BASE_BODY)
+            }
+        });
+        let api = Currencyapi::builder("key")
+            .base_fallback("USD")
+            .build()
+            .unwrap();
+        let res = api
+            .get_rates(server.path("latest?base_currency=XAG&currencies=EUR%2CGBP"))
+            .await
+            .unwrap();
+        let mut codes: Vec<&String> = res.data.keys().collect();
+        codes.sort();
+        assert_eq!(codes, ["EUR", "GBP"]);
+        assert!((res.data["EUR"].value.unwrap() - 22.5).abs() < 1e-9);
+        assert!((res.data["GBP"].value.unwrap() - 20.0).abs() < 1e-9);
+        let meta = res.meta.unwrap();
+        assert_eq!(meta.fallback_base.as_deref(), Some("USD"));
+        assert_eq!(meta.base_currency.as_deref(), Some("XAG"));
+        assert_eq!(
+            meta.last_updated_at.as_deref(),
+            Some("2024-01-01T23:59:59Z")
+        );
+    }
+
+    #[tokio::test]
+    async fn should_apply_non_finite_rates_policy() {
+        use crate::testing::{MockResponse, MockServer};
+
+        // rebasing onto the subnormal XAG rate overflows the EUR rate to infinity
+        let server = MockServer::start(|target| {
+            if target.contains("base_currency=USD") {
+                MockResponse::json(
+                    200,
+                    r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9},"GBP":{"code":"GBP","value":0.0},"XAG":{"code":"XAG","value":5e-324}}}"#,
+                )
+            } else {
+                MockResponse::json(422, UNSUPPORTED_BASE_BODY)
+            }
+        });
+        let url = server.path("latest?base_currency=XAG&currencies=EUR%2CGBP");
+        let api = |policy| {
+            Currencyapi::builder("key")
+                .base_fallback("USD")
+                .non_finite_rates(policy)
+                .build()
+                .unwrap()
+        };
+
+        let res = api(NonFiniteRates::Skip)
+            .get_rates(url.clone())
+            .await
+            .unwrap();
+        assert_eq!(res.data.keys().collect::<Vec<_>>(), ["GBP"]);
+        let res = api(NonFiniteRates::Keep)
+            .get_rates(url.clone())
+            .await
+            .unwrap();
+        assert_eq!(res.data["EUR"].value, Some(f64::INFINITY));
+        let err = api(NonFiniteRates::Reject)
+            .get_rates(url)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CurrencyapiError::InvalidResponseData { reason } if reason.contains("EUR")
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_apply_non_finite_rates_policy_to_missing_rates() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9},"VES":{"code":"VES","value":false}}}"#,
+            )
+        });
+        let url = server.path("latest");
+        let api = |policy| {
+            Currencyapi::builder("key")
+                .non_finite_rates(policy)
+                .build()
+                .unwrap()
+        };
+
+        let res = api(NonFiniteRates::Skip)
+            .get_rates(url.clone())
+            .await
+            .unwrap();
+        assert_eq!(res.data.keys().collect::<Vec<_>>(), ["EUR"]);
+        let res = api(NonFiniteRates::Keep)
+            .get_rates(url.clone())
+            .await
+            .unwrap();
+        assert_eq!(res.data["VES"].value, None);
+        let err = api(NonFiniteRates::Reject)
+            .get_rates(url)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CurrencyapiError::InvalidResponseData { reason } if reason == "rate of VES is missing"
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_report_unsupported_base_without_fallback() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|_| MockResponse::json(422, UNSUPPORTED_BASE_BODY));
+        let api = Currencyapi::new("key").unwrap();
+        let err = api
+            .get_rates(server.path("latest?base_currency=XAG&currencies=EUR"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CurrencyapiError::UnsupportedBaseCurrency { base } if base == "XAG"));
+    }
+
+    #[tokio::test]
+    async fn should_use_hedged_response_when_first_is_slow() {
+        use crate::testing::{MockResponse, MockServer};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let server = MockServer::start(move |_| match counter.fetch_add(1, Ordering::SeqCst) {
+            0 => MockResponse::json(200, r#"{"data":{"first":1},"meta":null}"#)
+                .delayed(Duration::from_secs(5)),
+            _ => MockResponse::json(200, r#"{"data":{"hedge":1},"meta":null}"#),
+        });
+        let api = Currencyapi::builder("key")
+            .hedge_after(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let started_at = std::time::Instant::now();
+        let res: models::DetailsResponse = api.get(server.path("latest")).await.unwrap();
+        assert!(started_at.elapsed() < Duration::from_secs(2));
+        assert!(res.data.contains_key("hedge"));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn should_not_hedge_fast_requests() {
+        use crate::testing::{MockResponse, MockServer};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let server = MockServer::start(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            MockResponse::json(200, r#"{"data":{},"meta":null}"#)
+        });
+        let api = Currencyapi::builder("key")
+            .hedge_after(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        let res: models::DetailsResponse = api.get(server.path("latest")).await.unwrap();
+        assert!(res.data.is_empty());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn should_accumulate_seen_codes_across_calls() {
+        let api = Currencyapi::builder("key")
+            .track_seen_codes(true)
+            .build()
+            .unwrap();
+        let first = [String::from("USD"), String::from("EUR")];
+        let second = [String::from("JPY"), String::from("EUR")];
+        api.record_codes(&first);
+        api.record_codes(&second);
+        let seen: Vec<String> = api.seen_codes().into_iter().collect();
+        assert_eq!(seen, vec!["EUR", "JPY", "USD"]);
+    }
+
+    #[test]
+    fn should_not_record_codes_when_tracking_is_disabled() {
+        let api = Currencyapi::new("key").unwrap();
+        api.record_codes(&[String::from("USD")]);
+        assert!(api.seen_codes().is_empty());
     }
 }
@@ -0,0 +1,24 @@
+//! Module that contains the [CallPlan] returned by the planning methods of a [Currencyapi] client
+
+#[cfg(doc)]
+use crate::api::Currencyapi;
+
+/// Number of api calls an operation would make, computed without sending any request.
+///
+/// Create a plan with the `plan_*` methods of [Currencyapi], e.g. [Currencyapi::plan_convert_pairs].
+/// The estimate does not include retries or hedged requests and assumes that no
+/// response is answered from the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct CallPlan {
+    /// Number of requests the operation would send
+    pub estimated_calls: u32,
+}
+
+impl CallPlan {
+    pub(crate) fn new(calls: usize) -> Self {
+        Self {
+            estimated_calls: u32::try_from(calls).unwrap_or(u32::MAX),
+        }
+    }
+}
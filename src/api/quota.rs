@@ -0,0 +1,76 @@
+//! Module that contains the [QuotaHeaders] returned by [Currencyapi::quota]
+
+#[cfg(doc)]
+use crate::api::Currencyapi;
+use reqwest::header::HeaderMap;
+
+/// Remaining requests reported by the rate limit headers of an api response, e.g. to
+/// throttle before the monthly quota is used up instead of running into status `429`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct QuotaHeaders {
+    /// Requests included in the monthly quota, from `X-RateLimit-Limit-Month`
+    pub limit_month: Option<u64>,
+    /// Requests left in the current month, from `X-RateLimit-Remaining-Month`
+    pub remaining_month: Option<u64>,
+    /// Requests allowed per minute, from `X-RateLimit-Limit-Minute`
+    pub limit_minute: Option<u64>,
+    /// Requests left in the current minute, from `X-RateLimit-Remaining-Minute`
+    pub remaining_minute: Option<u64>,
+}
+
+impl QuotaHeaders {
+    /// Reads the rate limit headers of a response, returning `None` if it has none of them.
+    /// The `X-RateLimit-*-Quota-*` spelling of the header names is accepted as well.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let value = |kind: &str, period: &str| {
+            [
+                format!("x-ratelimit-{}-{}", kind, period),
+                format!("x-ratelimit-{}-quota-{}", kind, period),
+            ]
+            .iter()
+            .find_map(|name| headers.get(name.as_str()))
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+        let quota = Self {
+            limit_month: value("limit", "month"),
+            remaining_month: value("remaining", "month"),
+            limit_minute: value("limit", "minute"),
+            remaining_minute: value("remaining", "minute"),
+        };
+        (quota != Self::default()).then_some(quota)
+    }
+}
+
+#[cfg(test)]
+mod quota_test {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn should_read_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(QuotaHeaders::from_headers(&headers), None);
+        headers.insert(
+            "X-RateLimit-Remaining-Month",
+            HeaderValue::from_static("228"),
+        );
+        headers.insert(
+            "X-RateLimit-Limit-Quota-Month",
+            HeaderValue::from_static("300"),
+        );
+        headers.insert(
+            "X-RateLimit-Remaining-Minute",
+            HeaderValue::from_static("many"),
+        );
+        assert_eq!(
+            QuotaHeaders::from_headers(&headers),
+            Some(QuotaHeaders {
+                limit_month: Some(300),
+                remaining_month: Some(228),
+                limit_minute: None,
+                remaining_minute: None,
+            })
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Module that contains the [Interaction] captured by [CurrencyapiBuilder::record_to]
+
+#[cfg(doc)]
+use crate::api::CurrencyapiBuilder;
+use reqwest::header::HeaderMap;
+use reqwest::{Request, StatusCode};
+use std::sync::{Arc, Mutex};
+
+/// Value replacing the `apikey` header in recorded interactions
+const REDACTED: &str = "REDACTED";
+
+/// Shared list recorded interactions are appended to, see [CurrencyapiBuilder::record_to]
+pub type Recording = Arc<Mutex<Vec<Interaction>>>;
+
+/// Request sent to the api together with the raw response received for it, e.g. to
+/// replay the traffic of an integration test through a mock server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interaction {
+    /// HTTP method of the request
+    pub method: String,
+    /// Url of the request including all query parameters
+    pub url: String,
+    /// Headers set on the request. Default headers of the http client like the
+    /// `User-Agent` and the `apikey` header are not included.
+    pub request_headers: Vec<(String, String)>,
+    /// Status code of the response
+    pub status: u16,
+    /// Headers of the response
+    pub response_headers: Vec<(String, String)>,
+    /// Body of the response exactly as received
+    pub body: Vec<u8>,
+}
+
+/// Request part of an [Interaction] captured before the request is sent.
+pub(crate) struct PendingInteraction {
+    method: String,
+    url: String,
+    request_headers: Vec<(String, String)>,
+}
+
+impl PendingInteraction {
+    /// Captures the method, url and redacted headers of `request`.
+    pub(crate) fn of(request: &Request) -> Self {
+        Self {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            request_headers: header_pairs(request.headers()),
+        }
+    }
+
+    /// Completes the interaction with the response received for the request.
+    pub(crate) fn complete(
+        self,
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Interaction {
+        Interaction {
+            method: self.method,
+            url: self.url,
+            request_headers: self.request_headers,
+            status: status.as_u16(),
+            response_headers: header_pairs(headers),
+            body: body.to_vec(),
+        }
+    }
+}
+
+/// Lists the headers as name and value pairs with the value of the `apikey` header redacted.
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if name == "apikey" {
+                String::from(REDACTED)
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
@@ -0,0 +1,27 @@
+//! Module that contains the [AppState] returned by [Currencyapi::init]
+
+#[cfg(doc)]
+use crate::api::Currencyapi;
+use crate::error::CurrencyapiError;
+use crate::models;
+
+/// Account status, currency metadata and latest rates fetched at once on startup.
+///
+/// Each part keeps its own result, so a failed request does not discard the parts
+/// that were fetched successfully.
+#[derive(Debug)]
+pub struct AppState {
+    /// Request quotas of the api key
+    pub status: Result<models::StatusResponse, CurrencyapiError>,
+    /// Metadata of all supported currencies
+    pub currencies: Result<models::CurrenciesResponse, CurrencyapiError>,
+    /// Latest rates of the requested currencies
+    pub latest: Result<models::LatestResponse, CurrencyapiError>,
+}
+
+impl AppState {
+    /// Returns `true` if all parts were fetched successfully.
+    pub fn is_complete(&self) -> bool {
+        self.status.is_ok() && self.currencies.is_ok() && self.latest.is_ok()
+    }
+}
@@ -0,0 +1,93 @@
+//! Module that contains the [ConfigSummary] of a configured [Currencyapi] client
+
+#[cfg(doc)]
+use crate::api::Currencyapi;
+use crate::api::{NonFiniteRates, NumberHandling};
+use crate::RetryConfig;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+/// Effective options of a [Currencyapi] client for diagnostics, e.g. to attach to a bug report.
+///
+/// Create a summary with [Currencyapi::config_summary]. It never contains the api key
+/// and can safely be printed with its `Debug` implementation.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct ConfigSummary {
+    /// Base url all requests are sent to
+    pub base_url: String,
+    /// Api version of the default base url or `None` if a custom base url is set
+    pub api_version: Option<String>,
+    /// `Accept` header sent with each request
+    pub accept: String,
+    /// How numbers in responses are handled
+    pub number_handling: NumberHandling,
+    /// How rates that are NaN or infinite are handled
+    pub non_finite_rates: NonFiniteRates,
+    /// Whether deprecated currency codes are replaced before sending a request
+    pub currency_aliases: bool,
+    /// Time after which cached responses expire or `None` if caching is disabled
+    pub cache_ttl: Option<Duration>,
+    /// Maximum number of cached responses or `None` if the cache is unbounded or disabled
+    pub cache_capacity: Option<NonZeroUsize>,
+    /// Retry policy or `None` if requests are not retried
+    pub retry: Option<RetryConfig>,
+    /// Decimal places of the convert `value` parameter or `None` for full precision
+    pub value_precision: Option<usize>,
+    /// Maximum absolute value of convert requests or `None` if there is no limit
+    pub max_convert_value: Option<f64>,
+    /// Whether an error hook is registered
+    pub error_hook: bool,
+    /// Whether responses with duplicate currency codes are rejected
+    pub reject_duplicate_currencies: bool,
+    /// Whether rate tables with a different base currency than requested are rejected
+    pub strict_base_currency: bool,
+    /// Whether currency codes of responses are recorded
+    pub track_seen_codes: bool,
+    /// Rate in requests per second and burst of the request limiter or `None` if disabled
+    pub leaky_bucket: Option<(f64, u32)>,
+    /// Base currency used if the requested one is not supported
+    pub base_fallback: Option<String>,
+    /// Delay after which a slow request is duplicated or `None` if requests are not hedged
+    pub hedge_after: Option<Duration>,
+    /// Time after which requests are aborted or `None` if requests never time out
+    pub timeout: Option<Duration>,
+    /// Time before the current day that is still treated as today
+    pub clock_skew_tolerance: Duration,
+    /// Whether the current day is taken from the `Date` header of api responses
+    pub server_clock: bool,
+    /// First day with historical rates that is requested
+    #[cfg(feature = "chrono")]
+    pub history_start: chrono::NaiveDate,
+    /// Maximum number of requests in flight at once
+    pub max_concurrency: NonZeroUsize,
+    /// Whether interactions are recorded
+    pub recording: bool,
+    /// Whether requests are sent with an http client provided by the caller
+    pub custom_http_client: bool,
+    /// User agent sent with each request or `None` if the http client was provided
+    /// by the caller
+    pub user_agent: Option<String>,
+    /// Cargo features the crate was compiled with
+    pub features: Vec<&'static str>,
+}
+
+/// Returns the optional cargo features that are enabled.
+pub(crate) fn enabled_features() -> Vec<&'static str> {
+    [
+        ("chrono", cfg!(feature = "chrono")),
+        ("rustls-tls", cfg!(feature = "rustls-tls")),
+        ("native-tls", cfg!(feature = "native-tls")),
+        ("opentelemetry", cfg!(feature = "opentelemetry")),
+        ("tracing", cfg!(feature = "tracing")),
+        ("simd-json", cfg!(feature = "simd-json")),
+        ("bincode", cfg!(feature = "bincode")),
+        ("blocking", cfg!(feature = "blocking")),
+        ("decimal", cfg!(feature = "decimal")),
+        ("validate", cfg!(feature = "validate")),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| name)
+    .collect()
+}
@@ -0,0 +1,213 @@
+//! Blocking client for the currencyapi, e.g. for command line tools without an async runtime.
+//!
+//! The client is only available with the `blocking` feature. It sends every request
+//! once with the plain [reqwest::blocking::Client], without caching, retries or rate limiting.
+//! Use the async [crate::Currencyapi] for these options.
+
+use crate::api::{
+    append_currency_type, convert_url, historical_url, join_codes, latest_url, range_url,
+    retry_after, status_error, Endpoint,
+};
+use crate::error::CurrencyapiError;
+use crate::models;
+use crate::utils::baseline::{
+    construct_blocking_client, parse_base_url, versioned_base_url, DEFAULT_API_VERSION,
+};
+use reqwest::blocking::Client;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+
+/// Blocking client for the currencyapi
+#[derive(Debug, Clone)]
+pub struct Currencyapi {
+    client: Client,
+    base_url: Url,
+}
+
+impl Currencyapi {
+    /// Creates a new instance of the blocking client.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the http client cannot be constructed.
+    pub fn new(api_key: impl AsRef<str>) -> Result<Self, CurrencyapiError> {
+        Ok(Self {
+            client: construct_blocking_client(api_key.as_ref())?,
+            base_url: versioned_base_url(DEFAULT_API_VERSION)?,
+        })
+    }
+
+    /// Creates a new instance of the blocking client that sends all requests to `base_url`
+    /// instead of the currencyapi.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [CurrencyapiError::UrlConstruction] if `base_url` is not
+    /// a valid http url or an error if the http client cannot be constructed.
+    pub fn with_base_url(
+        api_key: impl AsRef<str>,
+        base_url: impl AsRef<str>,
+    ) -> Result<Self, CurrencyapiError> {
+        let api_key = api_key.as_ref();
+        let base_url = base_url.as_ref();
+        Ok(Self {
+            client: construct_blocking_client(api_key)?,
+            base_url: parse_base_url(base_url)?,
+        })
+    }
+
+    /// Sends a request to `url` and parses the response body.
+    fn get<T: DeserializeOwned>(&self, url: Url) -> Result<T, CurrencyapiError> {
+        #[cfg(feature = "validate")]
+        crate::api::known_codes::validate_url(&url)?;
+        let response = self
+            .client
+            .get(url.clone())
+            .send()
+            .map_err(CurrencyapiError::request)?;
+        let status = response.status();
+        let retry_after = retry_after(response.headers());
+        let body = response.text().map_err(CurrencyapiError::request)?;
+        if !status.is_success() {
+            return Err(status_error(&url, status, retry_after, body));
+        }
+        serde_json::from_str(&body).map_err(|_| CurrencyapiError::ResponseParsingError { body })
+    }
+
+    /// Fetches the request quotas of the api key like [crate::Currencyapi::status].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    pub fn status(&self) -> Result<models::StatusResponse, CurrencyapiError> {
+        self.get(Endpoint::Status.url(&self.base_url)?)
+    }
+
+    /// Fetches the latest rates like [crate::Currencyapi::latest].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    pub fn latest(
+        &self,
+        base_currency: impl AsRef<str>,
+        currencies: &[&str],
+        currency_type: Option<models::CurrencyType>,
+    ) -> Result<models::LatestResponse, CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let mut url = latest_url(&self.base_url, base_currency, &join_codes(currencies))?;
+        append_currency_type(&mut url, currency_type);
+        self.get(url)
+    }
+
+    /// Fetches the rates of a past date like [crate::Currencyapi::historical].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    pub fn historical(
+        &self,
+        base_currency: impl AsRef<str>,
+        date: impl AsRef<str>,
+        currencies: &[&str],
+    ) -> Result<models::DetailsResponse, CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let date = date.as_ref();
+        self.get(historical_url(
+            &self.base_url,
+            base_currency,
+            date,
+            &join_codes(currencies),
+        )?)
+    }
+
+    /// Converts a value into other currencies like [crate::Currencyapi::convert].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    pub fn convert(
+        &self,
+        base_currency: impl AsRef<str>,
+        date: impl AsRef<str>,
+        value: f64,
+        currencies: &[&str],
+    ) -> Result<models::ConversionResult, CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let date = date.as_ref();
+        self.get(convert_url(
+            &self.base_url,
+            base_currency,
+            date,
+            &value.to_string(),
+            &join_codes(currencies),
+        )?)
+    }
+
+    /// Fetches the rates of a time range like [crate::Currencyapi::range].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or if the response cannot be parsed.
+    pub fn range(
+        &self,
+        base_currency: impl AsRef<str>,
+        datetime_start: impl AsRef<str>,
+        datetime_end: impl AsRef<str>,
+        currencies: &[&str],
+        accuracy: models::RangeAccuracy,
+    ) -> Result<models::RangeResponse, CurrencyapiError> {
+        let base_currency = base_currency.as_ref();
+        let datetime_start = datetime_start.as_ref();
+        let datetime_end = datetime_end.as_ref();
+        self.get(range_url(
+            &self.base_url,
+            base_currency,
+            datetime_start,
+            datetime_end,
+            &join_codes(currencies),
+            accuracy,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod blocking_test {
+    use super::*;
+    use crate::testing::{MockResponse, MockServer};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn should_send_blocking_requests() {
+        let targets = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&targets);
+        let server = MockServer::start_with_head(move |target, head| {
+            recorded.lock().unwrap().push(String::from(target));
+            assert!(head.contains("apikey: key"));
+            match target.split('?').next().unwrap_or_default() {
+                "/status" => MockResponse::json(
+                    200,
+                    r#"{"quotas":{"month":{"total":300,"used":72,"remaining":228}}}"#,
+                ),
+                "/latest" => MockResponse::json(
+                    200,
+                    r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9}}}"#,
+                ),
+                _ => MockResponse::json(422, r#"{"message":"Validation error"}"#),
+            }
+        });
+        let api = Currencyapi::with_base_url("key", server.url.as_str()).unwrap();
+        assert_eq!(api.status().unwrap().quotas.month.remaining, 228);
+        let res = api.latest("USD", &["EUR"], None).unwrap();
+        assert_eq!(res.data["EUR"].value, Some(0.9));
+        assert!(api.convert("USD", "2024-01-02", 1.5, &["EUR"]).is_err());
+        assert_eq!(
+            *targets.lock().unwrap(),
+            [
+                "/status",
+                "/latest?base_currency=USD&currencies=EUR",
+                "/convert?base_currency=USD&date=2024-01-02&value=1.5&currencies=EUR",
+            ]
+        );
+    }
+}
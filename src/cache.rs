@@ -0,0 +1,223 @@
+//! Module that contains the in-memory response cache, its [CacheStats] and the
+//! [DataSource] of a result
+
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Counters describing how effective the response cache is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    /// Number of requests answered from the cache
+    pub hits: u64,
+    /// Number of requests that had to be sent to the api
+    pub misses: u64,
+    /// Number of entries removed from the cache because they expired or
+    /// were the least recently used entry of a full cache
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// Returns the share of lookups answered from the cache or `0.0` if there were none.
+    pub fn hit_ratio(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / total as f64
+    }
+}
+
+/// Origin of the data of a result, see e.g. [Currencyapi::latest_with_source]
+///
+/// [Currencyapi::latest_with_source]: crate::Currencyapi::latest_with_source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataSource {
+    /// At least one response was received from the api
+    Network,
+    /// All responses were answered from the response cache
+    Cache {
+        /// Age of the oldest cached response
+        age: Duration,
+    },
+}
+
+impl DataSource {
+    /// Combines the sources of two responses merged into one result.
+    pub(crate) fn combine(self, other: DataSource) -> DataSource {
+        match (self, other) {
+            (DataSource::Cache { age }, DataSource::Cache { age: other }) => DataSource::Cache {
+                age: age.max(other),
+            },
+            _ => DataSource::Network,
+        }
+    }
+}
+
+/// A cached response body
+#[derive(Debug)]
+struct CacheEntry {
+    body: String,
+    inserted_at: Instant,
+}
+
+/// Cache of response bodies keyed by request url that expire after a fixed ttl.
+/// If a capacity is set the least recently used entry is evicted when the cache is full.
+#[derive(Debug)]
+pub(crate) struct ResponseCache {
+    ttl: Duration,
+    capacity: Option<NonZeroUsize>,
+    entries: Mutex<LruCache<String, CacheEntry>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(ttl: Duration, capacity: Option<NonZeroUsize>) -> Self {
+        let entries = match capacity {
+            Some(capacity) => LruCache::new(capacity),
+            None => LruCache::unbounded(),
+        };
+        Self {
+            ttl,
+            capacity,
+            entries: Mutex::new(entries),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the time after which entries expire.
+    pub(crate) fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns the maximum number of entries or `None` if the cache is unbounded.
+    pub(crate) fn capacity(&self) -> Option<NonZeroUsize> {
+        self.capacity
+    }
+
+    /// Returns the cached body for `key` and its age if it has not expired yet.
+    pub(crate) fn get(&self, key: &str) -> Option<(String, Duration)> {
+        let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        let age = entries.peek(key).map(|entry| entry.inserted_at.elapsed());
+        match age {
+            Some(age) if age <= self.ttl => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                entries.get(key).map(|entry| (entry.body.clone(), age))
+            }
+            Some(_) => {
+                entries.pop(key);
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Stores the body for `key`, replacing any previous entry and evicting the
+    /// least recently used entry if the cache is full.
+    pub(crate) fn insert(&self, key: String, body: String) {
+        let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        let entry = CacheEntry {
+            body,
+            inserted_at: Instant::now(),
+        };
+        if let Some((evicted, _)) = entries.push(key.clone(), entry) {
+            if evicted != key {
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Removes all entries without counting them as evictions.
+    pub(crate) fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clear();
+    }
+
+    /// Returns a snapshot of the cache counters.
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod cache_test {
+    use super::*;
+
+    #[test]
+    fn should_count_a_miss_then_a_hit() {
+        let cache = ResponseCache::new(Duration::from_secs(60), None);
+        assert_eq!(cache.get("latest?base_currency=USD"), None);
+        cache.insert(String::from("latest?base_currency=USD"), String::from("{}"));
+        let (body, _) = cache.get("latest?base_currency=USD").unwrap();
+        assert_eq!(body, "{}");
+        let stats = cache.stats();
+        assert_eq!(
+            stats,
+            CacheStats {
+                hits: 1,
+                misses: 1,
+                evictions: 0
+            }
+        );
+        assert_eq!(stats.hit_ratio(), 0.5);
+    }
+
+    #[test]
+    fn should_miss_after_clear() {
+        let cache = ResponseCache::new(Duration::from_secs(60), None);
+        cache.insert(String::from("status"), String::from("{}"));
+        cache.clear();
+        assert_eq!(cache.get("status"), None);
+        assert_eq!(cache.stats().evictions, 0);
+    }
+
+    #[test]
+    fn should_count_expired_entries_as_evictions() {
+        let cache = ResponseCache::new(Duration::ZERO, None);
+        cache.insert(String::from("status"), String::from("{}"));
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(cache.get("status"), None);
+        assert_eq!(cache.stats().evictions, 1);
+        assert_eq!(cache.stats().misses, 1);
+    }
+
+    #[test]
+    fn should_evict_least_recently_used_entry_past_capacity() {
+        let cache = ResponseCache::new(Duration::from_secs(60), NonZeroUsize::new(2));
+        cache.insert(String::from("a"), String::from("1"));
+        cache.insert(String::from("b"), String::from("2"));
+        cache.insert(String::from("c"), String::from("3"));
+        assert_eq!(cache.get("a"), None);
+        assert!(cache.get("b").is_some());
+        assert!(cache.get("c").is_some());
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn should_keep_recently_used_entry_past_capacity() {
+        let cache = ResponseCache::new(Duration::from_secs(60), NonZeroUsize::new(2));
+        cache.insert(String::from("a"), String::from("1"));
+        cache.insert(String::from("b"), String::from("2"));
+        assert!(cache.get("a").is_some());
+        cache.insert(String::from("c"), String::from("3"));
+        assert!(cache.get("a").is_some());
+        assert_eq!(cache.get("b"), None);
+    }
+}
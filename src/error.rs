@@ -1,15 +1,42 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 #[derive(Error, Debug)]
 
 /// Contains all possible errors of the crate
 pub enum CurrencyapiError {
-    /// Something went wrong during fetching of
-    /// the currencyapi api
+    /// Something went wrong during fetching of the currencyapi api
+    /// that is not a timeout, connect or read body error
     #[error("request to api failed")]
     RequestError {
         /// Error source
         #[source]
         source: reqwest::Error,
     },
+    /// The request to the api did not complete within the configured timeout
+    /// or the retry deadline
+    #[error("request to api timed out")]
+    Timeout {
+        /// Error source or `None` if the retry deadline passed before any attempt failed
+        #[source]
+        source: Option<reqwest::Error>,
+    },
+    /// No connection to the api could be established, e.g. because the host
+    /// cannot be resolved or refuses the connection
+    #[error("failed to connect to api")]
+    ConnectError {
+        /// Error source
+        #[source]
+        source: reqwest::Error,
+    },
+    /// The response of the api could not be read, e.g. because the connection
+    /// broke off while receiving the body
+    #[error("failed to read response body of api")]
+    ReadBodyError {
+        /// Error source
+        #[source]
+        source: reqwest::Error,
+    },
     /// Something went wrong during the parsing
     /// of the currencyapi api response.
     #[error("Failed to parse json response: '{body}'")]
@@ -17,6 +44,19 @@ pub enum CurrencyapiError {
         /// Response body that could not be parsed
         body: String,
     },
+    /// The api answered with an error status and its error message,
+    /// e.g. `401` for an invalid api key or `422` for invalid parameters
+    #[error("Api responded with status {status}: {message}")]
+    ApiError {
+        /// Http status of the response
+        status: u16,
+        /// Error message of the api
+        message: String,
+        /// Validation messages keyed by the name of the invalid parameter
+        errors: HashMap<String, Vec<String>>,
+        /// Delay requested by the `Retry-After` header before sending another request
+        retry_after: Option<Duration>,
+    },
     /// Something went wrong during header construction
     #[error("Failed to construct http header")]
     HeaderConstruction {
@@ -34,4 +74,148 @@ pub enum CurrencyapiError {
     /// Failed to parse the request url
     #[error("Failed to construct the url")]
     UrlConstruction,
+    /// A date parameter is not a valid date formatted as `YYYY-MM-DD`
+    #[error("Invalid date '{date}', expected YYYY-MM-DD")]
+    InvalidDate {
+        /// Rejected date
+        date: String,
+    },
+    /// An option passed to the builder is invalid
+    #[error("Invalid configuration: {reason}")]
+    InvalidConfiguration {
+        /// Description of the invalid option
+        reason: String,
+    },
+    /// The environment variable expected to hold the api key is unset or empty
+    #[error("Environment variable '{variable}' with the api key is not set")]
+    MissingApiKey {
+        /// Name of the environment variable
+        variable: String,
+    },
+    /// A number in the response cannot be represented exactly as `f64`
+    /// while strict number handling is enabled
+    #[error("Number '{value}' in response would lose precision")]
+    PrecisionLoss {
+        /// Number literal as contained in the response
+        value: String,
+    },
+    /// The value of a convert request exceeds the configured maximum
+    #[error("Value {value} exceeds the maximum convert value {max}")]
+    ValueOutOfRange {
+        /// Rejected value
+        value: f64,
+        /// Configured maximum absolute value
+        max: f64,
+    },
+    /// The amount to convert is NaN or infinite
+    #[error("Amount {value} is not a finite number")]
+    InvalidAmount {
+        /// Rejected amount
+        value: f64,
+    },
+    /// The api rejected the request because its url or payload is too large,
+    /// e.g. because too many currencies were requested at once
+    #[error("Request too large, api responded with status {status}")]
+    RequestTooLarge {
+        /// Http status of the response, `413` or `414`
+        status: u16,
+    },
+    /// The endpoint is not included in the current subscription plan
+    #[error("Endpoint '{endpoint}' is not available on your plan")]
+    EndpointNotAvailableOnPlan {
+        /// Name of the restricted endpoint
+        endpoint: String,
+    },
+    /// The response contains data that contradicts itself or the request
+    #[error("Invalid response data: {reason}")]
+    InvalidResponseData {
+        /// Description of the inconsistency
+        reason: String,
+    },
+    /// The base currency is not supported by the api or the current plan
+    #[error("Base currency '{base}' is not supported")]
+    UnsupportedBaseCurrency {
+        /// Requested base currency
+        base: String,
+    },
+    /// The base currency of a response differs from the requested one
+    /// while strict base currency checks are enabled
+    #[error("Requested base currency '{requested}' but got '{got}'")]
+    UnexpectedBaseCurrency {
+        /// Requested base currency
+        requested: String,
+        /// Base currency of the response or `unknown` if it cannot be determined
+        got: String,
+    },
+    /// Responses could not be merged because they contain different rates
+    /// for the same currency
+    #[error("Conflicting rates for currency '{code}' in merged responses")]
+    MergeConflict {
+        /// Currency code with conflicting rates
+        code: String,
+    },
+    /// Responses could not be merged because they are quoted against
+    /// different base currencies
+    #[error("Cannot merge responses with base currencies '{first}' and '{second}'")]
+    BaseCurrencyMismatch {
+        /// Base currency of the earlier response
+        first: String,
+        /// Differing base currency of the later response
+        second: String,
+    },
+    /// A currency code appears more than once in a response
+    /// while duplicate detection is enabled
+    #[error("Currency '{code}' appears more than once in response")]
+    DuplicateCurrency {
+        /// Duplicated currency code
+        code: String,
+    },
+    /// A requested currency code is not contained in the bundled list of known codes
+    /// while the `validate` feature is enabled, so the request was not sent
+    #[error("Currency '{code}' is not a known currency code")]
+    UnknownCurrency {
+        /// Rejected currency code
+        code: String,
+    },
+    /// The requested currency was not part of the api response
+    #[error("Currency '{code}' not found in response")]
+    CurrencyNotFound {
+        /// Currency code that was missing
+        code: String,
+    },
+    /// Some currencies of a portfolio could not be converted into the target currency
+    #[error("Could not convert currencies {codes:?}")]
+    UnconvertibleCurrencies {
+        /// Currency codes without a rate, in order of their first appearance
+        codes: Vec<String>,
+    },
+    /// A binary snapshot of a rate table could not be encoded or decoded
+    #[error("Failed to encode or decode snapshot: {reason}")]
+    SnapshotError {
+        /// Description of the failure
+        reason: String,
+    },
+}
+
+impl CurrencyapiError {
+    /// Wraps an error of a request to the api, distinguishing timeouts, connection failures
+    /// and failures to read the body from other failures.
+    pub(crate) fn request(source: reqwest::Error) -> Self {
+        if source.is_timeout() {
+            CurrencyapiError::Timeout {
+                source: Some(source),
+            }
+        } else if source.is_connect() {
+            CurrencyapiError::ConnectError { source }
+        } else if source.is_decode() || source.is_body() {
+            CurrencyapiError::ReadBodyError { source }
+        } else {
+            CurrencyapiError::RequestError { source }
+        }
+    }
+
+    /// Returns `true` if the request to the api timed out.
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, CurrencyapiError::Timeout { .. })
+    }
 }
\ No newline at end of file
@@ -0,0 +1,105 @@
+//! Module that contains the rolling window of request latencies and its [LatencyStats]
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Number of recent request latencies kept to compute [LatencyStats]
+pub(crate) const LATENCY_WINDOW: usize = 1024;
+
+/// Percentiles of the latencies of recent requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LatencyStats {
+    /// Median latency
+    pub p50: Duration,
+    /// Latency not exceeded by 90 percent of the requests
+    pub p90: Duration,
+    /// Latency not exceeded by 99 percent of the requests
+    pub p99: Duration,
+    /// Number of requests the percentiles are computed from
+    pub count: usize,
+}
+
+/// Ring buffer of the latencies of the most recent requests
+#[derive(Debug)]
+pub(crate) struct LatencyWindow {
+    capacity: usize,
+    samples: Mutex<VecDeque<Duration>>,
+}
+
+impl LatencyWindow {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            samples: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Adds the latency of a request, dropping the oldest one if the window is full.
+    pub(crate) fn record(&self, latency: Duration) {
+        let mut samples = self.samples.lock().unwrap_or_else(|err| err.into_inner());
+        if samples.len() == self.capacity {
+            samples.pop_front();
+        }
+        samples.push_back(latency);
+    }
+
+    /// Returns the nearest-rank percentiles of the latencies in the window.
+    pub(crate) fn stats(&self) -> LatencyStats {
+        let mut sorted: Vec<Duration> = self
+            .samples
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .iter()
+            .copied()
+            .collect();
+        sorted.sort_unstable();
+        let percentile = |p: usize| {
+            let rank = (p * sorted.len()).div_ceil(100);
+            sorted
+                .get(rank.saturating_sub(1))
+                .copied()
+                .unwrap_or_default()
+        };
+        LatencyStats {
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            count: sorted.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod latency_test {
+    use super::*;
+
+    #[test]
+    fn should_compute_percentiles_of_synthetic_latencies() {
+        let window = LatencyWindow::new(LATENCY_WINDOW);
+        for millis in (1..=100).rev() {
+            window.record(Duration::from_millis(millis));
+        }
+        let stats = window.stats();
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.p50, Duration::from_millis(50));
+        assert_eq!(stats.p90, Duration::from_millis(90));
+        assert_eq!(stats.p99, Duration::from_millis(99));
+    }
+
+    #[test]
+    fn should_only_keep_most_recent_latencies() {
+        let window = LatencyWindow::new(3);
+        for millis in [500, 1, 2, 3] {
+            window.record(Duration::from_millis(millis));
+        }
+        let stats = window.stats();
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.p99, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn should_return_zero_stats_without_requests() {
+        assert_eq!(LatencyWindow::new(8).stats(), LatencyStats::default());
+    }
+}
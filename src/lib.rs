@@ -7,6 +7,7 @@
 //! * Async api calls with [reqwest][reqwest]
 //! * Ready deserialized structs of the currencyapi responses
 //! * Manages authentication for you, just pass your api token once
+//! * Common types importable at once with `use currencyapi_rs::prelude::*;`
 //!
 //! ## Requirements
 //! * Your own [currencyapi api key][currencyapi_api]
@@ -18,13 +19,42 @@
 //!
 //!
 //! ## Optional Features
-//!
+//! * `chrono` (default) - Date and time helpers such as staleness checks based on [chrono][chrono]
+//! * `rustls-tls` (default) - Connects to the api over TLS with [rustls][rustls], e.g. for static
+//!   musl binaries without OpenSSL
+//! * `native-tls` - Connects to the api over TLS with the platform's native TLS library, i.e.
+//!   OpenSSL on Linux. Disable the default features to drop rustls:
+//!   `default-features = false, features = ["chrono", "native-tls"]`. Without either TLS
+//!   feature only plain http base urls can be reached
+//! * `opentelemetry` - Wraps each request in an [OpenTelemetry][opentelemetry] client span
+//!   using the global tracer provider
+//! * `tracing` - Wraps the `status`, `latest`, `historical`, `convert` and `range` calls in
+//!   [tracing][tracing] spans with the endpoint, base currency and http status, and emits
+//!   a debug event with the request url and an error event for failed calls
+//! * `simd-json` - Parses response bodies with [simd-json][simd_json] instead of `serde_json`
+//! * `bincode` - Compact binary snapshots of rate tables with [bincode][bincode], e.g. to
+//!   persist the last fetched rates across restarts
+//! * `blocking` - Synchronous client in the `blocking` module for use without an async runtime
+//! * `decimal` - Rate tables with [rust_decimal][rust_decimal] rates for calculations
+//!   without floating point rounding errors
+//! * `validate` - Checks the base currency and requested currencies against a bundled list
+//!   of ISO 4217 and common crypto currency codes before sending a request and fails with
+//!   `UnknownCurrency` instead of spending quota on a typo. Leave it disabled to request
+//!   codes the list does not know yet
 //!
 //! ## Troubleshooting
+//! Errors reported by the api itself, e.g. for an invalid api key or invalid parameters,
+//! are returned as `ApiError` with the http status and the message of the api.
+//!
+//! Rates the api returns as `false` or `null` instead of a number are read as a missing
+//! `value` instead of failing the whole response. By default currencies with a missing
+//! rate are removed from the table, which can be changed with `non_finite_rates` of the
+//! builder.
+//!
 //! If you get a ResponseParsingError during usage of the crate this is very likely
 //! due to an invalid input where the currencyapi api will throw an error or
-//! due to some unexpected values that were returned by the api. E.g. sometimes the api
-//! will return `false` instead of a number for certain fields or other fields were missing.
+//! due to some unexpected values that were returned by the api, e.g. fields that were
+//! missing.
 //!
 //! In this case please check if your input is valid and if so create a bug report on the
 //! crate [repository][currencyapi_rs_repo] and provide some information about your input.
@@ -34,6 +64,13 @@
 //! [currencyapi_api]: https://creativecommons.currencyapi.de/
 //! [reqwest]: https://crates.io/crates/reqwest
 //! [tokio]: https://crates.io/crates/tokio
+//! [chrono]: https://crates.io/crates/chrono
+//! [opentelemetry]: https://crates.io/crates/opentelemetry
+//! [rustls]: https://crates.io/crates/rustls
+//! [tracing]: https://crates.io/crates/tracing
+//! [simd_json]: https://crates.io/crates/simd-json
+//! [bincode]: https://crates.io/crates/bincode
+//! [rust_decimal]: https://crates.io/crates/rust_decimal
 
 #![warn(missing_docs)]
 #![deny(rustdoc::bare_urls)]
@@ -48,12 +85,28 @@ extern crate strum;
 #[macro_use]
 extern crate thiserror;
 
+pub mod aliases;
 pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
 mod error;
+pub mod latency;
+mod limiter;
 /// This module contains the data structures used for deserializing
 /// the responses from the currencyapi API.pub mod models;
 pub mod models;
+pub mod prelude;
+pub mod retry;
+pub mod rounding;
+#[cfg(feature = "opentelemetry")]
+mod telemetry;
+#[cfg(test)]
+mod testing;
 mod utils;
 
+pub use aliases::CurrencyAliases;
 pub use api::Currencyapi;
 pub use error::CurrencyapiError as Error;
+pub use retry::RetryConfig;
+pub use rounding::RoundingMode;
@@ -0,0 +1,147 @@
+//! Module that contains the client side request limiter
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source of the current time, replaceable in tests.
+pub(crate) trait Clock: Debug + Send + Sync {
+    /// Returns the current instant.
+    fn now(&self) -> Instant;
+}
+
+/// [Clock] backed by [Instant::now].
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Leaky-bucket limiter that lets a burst of requests pass at once and then
+/// drains at a steady rate.
+///
+/// Each request adds one unit to the bucket, which leaks one unit per `interval`.
+/// Requests that would overflow a bucket of size `burst` are delayed until enough
+/// has leaked out. Delays are reserved on acquisition, so concurrent callers are
+/// spread out evenly instead of all waking up at once.
+#[derive(Debug)]
+pub(crate) struct LeakyBucket {
+    rate: f64,
+    burst: u32,
+    interval: Duration,
+    tolerance: Duration,
+    clock: Arc<dyn Clock>,
+    /// Instant at which the bucket is empty again
+    empty_at: Mutex<Option<Instant>>,
+}
+
+impl LeakyBucket {
+    /// Creates a limiter draining `rate` requests per second with room for `burst`
+    /// requests, or `None` if `rate` is not a positive finite number.
+    pub(crate) fn new(rate: f64, burst: u32, clock: Arc<dyn Clock>) -> Option<Self> {
+        if !rate.is_finite() || rate <= 0.0 {
+            return None;
+        }
+        let interval = Duration::try_from_secs_f64(1.0 / rate).ok()?;
+        let burst = burst.max(1);
+        Some(Self {
+            rate,
+            burst,
+            interval,
+            tolerance: interval.saturating_mul(burst - 1),
+            clock,
+            empty_at: Mutex::new(None),
+        })
+    }
+
+    /// Returns the number of requests drained per second.
+    pub(crate) fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Returns the number of requests that may be sent at once.
+    pub(crate) fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    /// Reserves room for one request and returns how long the caller has to wait
+    /// before sending it.
+    pub(crate) fn reserve(&self) -> Duration {
+        let now = self.clock.now();
+        let mut empty_at = self.empty_at.lock().unwrap_or_else(|err| err.into_inner());
+        let level_end = empty_at.map_or(now, |empty_at| empty_at.max(now));
+        let wait = level_end
+            .checked_sub(self.tolerance)
+            .map_or(Duration::ZERO, |allowed_at| {
+                allowed_at.saturating_duration_since(now)
+            });
+        *empty_at = Some(level_end + self.interval);
+        wait
+    }
+
+    /// Waits until the next request may be sent.
+    pub(crate) async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod limiter_test {
+    use super::*;
+
+    /// [Clock] that only moves when advanced manually
+    #[derive(Debug)]
+    struct ManualClock(Mutex<Instant>);
+
+    impl ManualClock {
+        fn advance(&self, by: Duration) {
+            *self.0.lock().unwrap() += by;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    fn bucket(rate: f64, burst: u32) -> (LeakyBucket, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock(Mutex::new(Instant::now())));
+        (LeakyBucket::new(rate, burst, clock.clone()).unwrap(), clock)
+    }
+
+    #[test]
+    fn should_allow_burst_then_pace_requests() {
+        let (bucket, _) = bucket(2.0, 3);
+        let waits: Vec<Duration> = (0..6).map(|_| bucket.reserve()).collect();
+        assert_eq!(waits, [0, 0, 0, 500, 1000, 1500].map(Duration::from_millis));
+    }
+
+    #[test]
+    fn should_refill_burst_after_draining() {
+        let (bucket, clock) = bucket(2.0, 2);
+        assert_eq!(bucket.reserve(), Duration::ZERO);
+        assert_eq!(bucket.reserve(), Duration::ZERO);
+        assert_eq!(bucket.reserve(), Duration::from_millis(500));
+        clock.advance(Duration::from_millis(750));
+        assert_eq!(bucket.reserve(), Duration::from_millis(250));
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(bucket.reserve(), Duration::ZERO);
+        assert_eq!(bucket.reserve(), Duration::ZERO);
+        assert_eq!(bucket.reserve(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn should_reject_invalid_rate() {
+        let clock = Arc::new(SystemClock);
+        assert!(LeakyBucket::new(0.0, 1, clock.clone()).is_none());
+        assert!(LeakyBucket::new(-1.0, 1, clock.clone()).is_none());
+        assert!(LeakyBucket::new(f64::NAN, 1, clock).is_none());
+    }
+}
@@ -0,0 +1,116 @@
+//! Typed response of the `convert` endpoint
+
+use crate::models::{CurrencyRate, Meta};
+#[cfg(feature = "chrono")]
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+/// Response of the `convert` endpoint
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct ConversionResult {
+    /// Converted values keyed by target currency code
+    pub data: HashMap<String, CurrencyRate>,
+    /// Meta information of the response
+    pub meta: Option<Meta>,
+}
+
+impl ConversionResult {
+    /// Returns the converted amount in the currency `code` or `None` if it is missing.
+    pub fn amount(&self, code: &str) -> Option<f64> {
+        self.data.get(code).and_then(|rate| rate.value)
+    }
+
+    /// Returns the timestamp the converted value of `code` is based on.
+    ///
+    /// Uses the timestamp of the currency itself if the api provides one and
+    /// falls back to `last_updated_at` of the response meta otherwise.
+    pub fn as_of(&self, code: &str) -> Option<&str> {
+        let rate = self.data.get(code)?;
+        rate.as_of.as_deref().or_else(|| {
+            self.meta
+                .as_ref()
+                .and_then(|meta| meta.last_updated_at.as_deref())
+        })
+    }
+
+    /// Returns the time the rates of the conversion were last updated or `None` if the
+    /// meta or its `last_updated_at` timestamp is missing or invalid.
+    #[cfg(feature = "chrono")]
+    pub fn last_updated_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.meta.as_ref().and_then(Meta::last_updated)
+    }
+}
+
+/// Result of a conversion with the average rate of a range, see
+/// [Currencyapi::convert_range_average](crate::api::Currencyapi::convert_range_average)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AveragedConversion {
+    /// Converted value in the target currency
+    pub value: f64,
+    /// Average rate from the base into the target currency used for the conversion
+    pub rate: f64,
+    /// Number of rates within the range the average is based on
+    pub samples: usize,
+}
+
+/// Kind of rates a conversion is based on
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateSource {
+    /// Rates of the `latest` endpoint
+    Latest,
+    /// Rates of the `historical` endpoint
+    Historical,
+}
+
+/// Result of a conversion with the rate and the date it is based on
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatedConversion {
+    /// Converted value in the target currency
+    pub value: f64,
+    /// Rate from the base into the target currency
+    pub rate: f64,
+    /// Date of the rate
+    pub date: NaiveDate,
+    /// Endpoint the rate was fetched from
+    pub source: RateSource,
+}
+
+#[cfg(test)]
+mod convert_test {
+    use super::*;
+
+    #[test]
+    fn should_return_converted_amount() {
+        let body = r#"{"meta":{"last_updated_at":"2024-03-01T23:59:59Z"},"data":{"EUR":{"code":"EUR","value":92.1064713},"GBP":{"code":"GBP","value":79.0218132}}}"#;
+        let res: ConversionResult = serde_json::from_str(body).unwrap();
+        assert_eq!(res.amount("EUR"), Some(92.1064713));
+        assert_eq!(res.amount("GBP"), Some(79.0218132));
+        assert_eq!(res.amount("JPY"), None);
+    }
+
+    #[test]
+    fn should_use_per_currency_timestamps() {
+        let body = r#"{
+            "meta": {"last_updated_at": "2024-03-01T23:59:59Z"},
+            "data": {
+                "EUR": {"code": "EUR", "value": 92.1, "as_of": "2024-03-01T23:59:59Z"},
+                "NGN": {"code": "NGN", "value": 158012.5, "as_of": "2024-03-01T12:00:00Z"},
+                "JPY": {"code": "JPY", "value": 15012.0}
+            }
+        }"#;
+        let res: ConversionResult = serde_json::from_str(body).unwrap();
+        assert_eq!(res.as_of("EUR"), Some("2024-03-01T23:59:59Z"));
+        assert_eq!(res.as_of("NGN"), Some("2024-03-01T12:00:00Z"));
+        assert_eq!(res.as_of("JPY"), Some("2024-03-01T23:59:59Z"));
+        assert_eq!(res.as_of("USD"), None);
+    }
+
+    #[test]
+    fn should_have_no_timestamp_without_meta() {
+        let body = r#"{"data": {"EUR": {"code": "EUR", "value": 92.1}}, "meta": null}"#;
+        let res: ConversionResult = serde_json::from_str(body).unwrap();
+        assert_eq!(res.as_of("EUR"), None);
+    }
+}
@@ -0,0 +1,226 @@
+//! Typed response of the `currencies` endpoint
+
+use serde::Deserializer;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Kind of a currency, converted from and to strings like `fiat` or `crypto`
+#[derive(
+    Debug,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Clone,
+    Copy,
+    strum::Display,
+    strum::EnumString,
+    strum::AsRefStr,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum CurrencyType {
+    /// Currency issued by a government, e.g. `EUR`
+    Fiat,
+    /// Cryptocurrency, e.g. `BTC`
+    Crypto,
+    /// Precious metal, e.g. `XAU`
+    Metal,
+}
+
+/// Metadata of a single currency
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct CurrencyMetadata {
+    /// Currency code
+    pub code: String,
+    /// English name of the currency
+    pub name: String,
+    /// English plural name of the currency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_plural: Option<String>,
+    /// International symbol of the currency
+    pub symbol: String,
+    /// Symbol of the currency in its local script
+    pub symbol_native: String,
+    /// Number of minor unit digits, e.g. `2` for cents
+    pub decimal_digits: u32,
+    /// Rounding increment for cash amounts or `0` if there is none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rounding: Option<f64>,
+    /// Kind of the currency or `None` if the api did not report a known kind
+    #[serde(
+        rename = "type",
+        default,
+        deserialize_with = "known_currency_type",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub currency_type: Option<CurrencyType>,
+    /// Number of units the rates of the currency are quoted per, e.g. `100` if a rate
+    /// is given per 100 units, or `None` if the rates are quoted per single unit.
+    /// The api does not return it, so it is only set on metadata populated by the caller,
+    /// see [Currencyapi::set_quote_scales](crate::api::Currencyapi::set_quote_scales)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+}
+
+/// Response of the `currencies` endpoint
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct CurrenciesResponse {
+    /// Currency metadata keyed by currency code
+    pub data: HashMap<String, CurrencyMetadata>,
+}
+
+/// Change of a single metadata field between two snapshots
+#[derive(Debug, PartialEq, Clone)]
+pub struct FieldChange {
+    /// Name of the field as returned by the api
+    pub field: String,
+    /// Value in the older snapshot or `None` if the field was missing
+    pub old: Option<Value>,
+    /// Value in the newer snapshot or `None` if the field was removed
+    pub new: Option<Value>,
+}
+
+/// Differences between two snapshots of currency metadata, see [CurrenciesResponse::diff]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MetadataDiff {
+    /// Sorted codes only contained in the newer snapshot
+    pub added: Vec<String>,
+    /// Sorted codes only contained in the older snapshot
+    pub removed: Vec<String>,
+    /// Changed fields keyed by the code of currencies contained in both snapshots
+    pub changed: BTreeMap<String, Vec<FieldChange>>,
+}
+
+impl MetadataDiff {
+    /// Returns `true` if both snapshots contain the same metadata.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl CurrenciesResponse {
+    /// Returns the number of units the rates of `code` are quoted per, defaulting to `1.0`
+    /// for unknown currencies and for scales that are not positive finite numbers.
+    pub fn scale(&self, code: &str) -> f64 {
+        self.data
+            .get(code)
+            .and_then(|metadata| metadata.scale)
+            .filter(|scale| scale.is_finite() && *scale > 0.0)
+            .unwrap_or(1.0)
+    }
+
+    /// Compares this snapshot with a newer one and reports added and removed
+    /// currencies as well as changed fields of currencies contained in both.
+    pub fn diff(&self, other: &CurrenciesResponse) -> MetadataDiff {
+        let mut diff = MetadataDiff::default();
+        for (code, old) in &self.data {
+            match other.data.get(code) {
+                Some(new) if new != old => {
+                    diff.changed.insert(code.clone(), field_changes(old, new));
+                }
+                Some(_) => {}
+                None => diff.removed.push(code.clone()),
+            }
+        }
+        diff.added = other
+            .data
+            .keys()
+            .filter(|code| !self.data.contains_key(*code))
+            .cloned()
+            .collect();
+        diff.added.sort();
+        diff.removed.sort();
+        diff
+    }
+}
+
+/// Deserializes the kind of a currency, mapping kinds unknown to this crate to `None`
+/// so that a new kind does not break parsing the whole response.
+fn known_currency_type<'de, D>(deserializer: D) -> Result<Option<CurrencyType>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<Value> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(value.and_then(|value| serde_json::from_value(value).ok()))
+}
+
+/// Lists the fields that differ between two metadata entries, sorted by field name.
+fn field_changes(old: &CurrencyMetadata, new: &CurrencyMetadata) -> Vec<FieldChange> {
+    let fields = |metadata: &CurrencyMetadata| match serde_json::to_value(metadata) {
+        Ok(Value::Object(fields)) => fields.into_iter().collect::<BTreeMap<_, _>>(),
+        _ => BTreeMap::new(),
+    };
+    let mut old = fields(old);
+    let mut new = fields(new);
+    let names: Vec<String> = old.keys().chain(new.keys()).cloned().collect();
+    let mut changes: Vec<FieldChange> = Vec::new();
+    for field in names {
+        let (old, new) = (old.remove(&field), new.remove(&field));
+        if old != new {
+            changes.push(FieldChange { field, old, new });
+        }
+    }
+    changes.sort_by(|a, b| a.field.cmp(&b.field));
+    changes
+}
+
+#[cfg(test)]
+mod currencies_test {
+    use super::*;
+    use serde_json::json;
+
+    const OLD: &str = r#"{"data":{
+        "EUR":{"symbol":"€","name":"Euro","symbol_native":"€","decimal_digits":2,"rounding":0,"code":"EUR","name_plural":"Euros","type":"fiat"},
+        "HRK":{"symbol":"kn","name":"Croatian Kuna","symbol_native":"kn","decimal_digits":2,"rounding":0,"code":"HRK","name_plural":"Croatian kunas","type":"fiat"},
+        "USD":{"symbol":"$","name":"US Dollar","symbol_native":"$","decimal_digits":2,"rounding":0,"code":"USD","name_plural":"US dollars","type":"fiat"}
+    }}"#;
+
+    const NEW: &str = r#"{"data":{
+        "EUR":{"symbol":"€","name":"Euro","symbol_native":"€","decimal_digits":2,"rounding":0,"code":"EUR","name_plural":"Euros","type":"fiat"},
+        "USD":{"symbol":"US$","name":"US Dollar","symbol_native":"$","decimal_digits":2,"rounding":0,"code":"USD","name_plural":"US dollars","type":"fiat"},
+        "XAU":{"symbol":"XAU","name":"Gold (troy ounce)","symbol_native":"XAU","decimal_digits":4,"rounding":0,"code":"XAU","name_plural":"Gold troy ounces","type":"metal"}
+    }}"#;
+
+    #[test]
+    fn should_report_added_removed_and_modified_currencies() {
+        let old: CurrenciesResponse = serde_json::from_str(OLD).unwrap();
+        let new: CurrenciesResponse = serde_json::from_str(NEW).unwrap();
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, ["XAU"]);
+        assert_eq!(diff.removed, ["HRK"]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            diff.changed["USD"],
+            [FieldChange {
+                field: String::from("symbol"),
+                old: Some(json!("$")),
+                new: Some(json!("US$")),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_parse_currency_type() {
+        let new: CurrenciesResponse = serde_json::from_str(NEW).unwrap();
+        assert_eq!(new.data["EUR"].currency_type, Some(CurrencyType::Fiat));
+        assert_eq!(new.data["XAU"].currency_type, Some(CurrencyType::Metal));
+        let unknown: CurrencyMetadata = serde_json::from_str(
+            r#"{"symbol":"X","name":"X","symbol_native":"X","decimal_digits":2,"code":"XXX","type":"token"}"#,
+        )
+        .unwrap();
+        assert_eq!(unknown.currency_type, None);
+        assert_eq!(CurrencyType::Crypto.to_string(), "crypto");
+        assert_eq!("metal".parse::<CurrencyType>(), Ok(CurrencyType::Metal));
+        assert!("token".parse::<CurrencyType>().is_err());
+    }
+
+    #[test]
+    fn should_report_no_changes_for_identical_snapshots() {
+        let old: CurrenciesResponse = serde_json::from_str(OLD).unwrap();
+        assert!(old.diff(&old.clone()).is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+//! Rate tables with [Decimal] rates for calculations without floating point rounding errors
+
+use crate::models::Meta;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Exchange rate of a single currency relative to the base currency as [Decimal]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct CurrencyRateDecimal {
+    /// Currency code
+    pub code: String,
+    /// Exchange rate relative to the base currency
+    pub value: Decimal,
+    /// ISO-8601 timestamp the rate is effective at, if the api provides it per currency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<String>,
+}
+
+/// Response of the `latest` endpoint with [Decimal] rates
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct LatestResponseDecimal {
+    /// Exchange rates keyed by currency code
+    pub data: HashMap<String, CurrencyRateDecimal>,
+    /// Meta information of the response
+    pub meta: Option<Meta>,
+}
+
+impl LatestResponseDecimal {
+    /// Converts `amount` from one currency of the table into another using the cross rate
+    /// of both currencies relative to the base currency.
+    ///
+    /// Returns `None` if either currency is missing, the rate of `from` is zero or the
+    /// result overflows.
+    pub fn convert(&self, from: &str, to: &str, amount: Decimal) -> Option<Decimal> {
+        let from_rate = self.data.get(from)?.value;
+        let to_rate = self.data.get(to)?.value;
+        amount.checked_mul(to_rate)?.checked_div(from_rate)
+    }
+}
+
+#[cfg(test)]
+mod decimal_test {
+    use super::*;
+    use std::str::FromStr;
+
+    const BODY: &str = r#"{"meta":{"last_updated_at":"2024-01-01T23:59:59Z"},"data":{"EUR":{"code":"EUR","value":0.9134},"JPY":{"code":"JPY","value":148.1},"XXX":{"code":"XXX","value":0}}}"#;
+
+    fn decimal(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn should_parse_rates_as_decimal() {
+        let res: LatestResponseDecimal = serde_json::from_str(BODY).unwrap();
+        assert_eq!(res.data["EUR"].value, decimal("0.9134"));
+        assert_eq!(res.data["JPY"].value, decimal("148.1"));
+    }
+
+    #[test]
+    fn should_convert_without_rounding_errors() {
+        let res: LatestResponseDecimal = serde_json::from_str(BODY).unwrap();
+        let total: Decimal = (0..1000)
+            .filter_map(|_| res.convert("EUR", "EUR", decimal("0.1")))
+            .sum();
+        assert_eq!(total, decimal("100"));
+        assert_eq!(
+            res.convert("EUR", "JPY", decimal("9.134")),
+            Some(decimal("1481"))
+        );
+        assert_eq!(res.convert("XXX", "EUR", decimal("1")), None);
+        assert_eq!(res.convert("EUR", "ABC", decimal("1")), None);
+    }
+}
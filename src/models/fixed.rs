@@ -0,0 +1,92 @@
+//! Rates of a fixed set of currencies deserialized into named fields
+
+/// Rates of USD, EUR, GBP and JPY read directly from the `data` map of a `latest`
+/// or `historical` response, e.g. for hot loops that always need the same currencies
+/// without map lookups.
+///
+/// Other currencies of the response are ignored, while a missing one fails the
+/// deserialization. Fetch it with [Currencyapi::latest_as](crate::api::Currencyapi::latest_as)
+/// or parse a response body with `serde_json`.
+///
+/// Own structs for other currency sets follow the same pattern: derive `Deserialize` for
+/// an envelope with a `data` field holding a struct with one field per currency code,
+/// each containing the `value` of the rate, and convert it with `#[serde(from = "...")]`.
+#[derive(Debug, Deserialize, PartialEq, Clone, Copy)]
+#[serde(from = "FixedEnvelope")]
+pub struct FixedRates {
+    /// Rate of the US dollar
+    pub usd: f64,
+    /// Rate of the euro
+    pub eur: f64,
+    /// Rate of the British pound
+    pub gbp: f64,
+    /// Rate of the Japanese yen
+    pub jpy: f64,
+}
+
+/// Response layout the [FixedRates] are read from
+#[derive(Deserialize)]
+struct FixedEnvelope {
+    data: FixedData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct FixedData {
+    usd: FixedRate,
+    eur: FixedRate,
+    gbp: FixedRate,
+    jpy: FixedRate,
+}
+
+#[derive(Deserialize)]
+struct FixedRate {
+    value: f64,
+}
+
+impl From<FixedEnvelope> for FixedRates {
+    fn from(envelope: FixedEnvelope) -> Self {
+        let data = envelope.data;
+        Self {
+            usd: data.usd.value,
+            eur: data.eur.value,
+            gbp: data.gbp.value,
+            jpy: data.jpy.value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod fixed_test {
+    use super::*;
+
+    #[test]
+    fn should_read_fixed_currencies_from_data() {
+        let body = r#"{
+            "meta": {"last_updated_at": "2024-03-01T23:59:59Z"},
+            "data": {
+                "CHF": {"code": "CHF", "value": 0.88},
+                "EUR": {"code": "EUR", "value": 0.92},
+                "GBP": {"code": "GBP", "value": 0.79},
+                "JPY": {"code": "JPY", "value": 149.73},
+                "USD": {"code": "USD", "value": 1.0}
+            }
+        }"#;
+        let rates: FixedRates = serde_json::from_str(body).unwrap();
+        assert_eq!(
+            rates,
+            FixedRates {
+                usd: 1.0,
+                eur: 0.92,
+                gbp: 0.79,
+                jpy: 149.73,
+            }
+        );
+    }
+
+    #[test]
+    fn should_fail_if_a_fixed_currency_is_missing() {
+        let body = r#"{"data": {"USD": {"code": "USD", "value": 1.0}}}"#;
+        assert!(serde_json::from_str::<FixedRates>(body).is_err());
+    }
+}
@@ -0,0 +1,809 @@
+//! Typed response of the `latest` endpoint
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use crate::error::CurrencyapiError;
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Unexpected, Visitor};
+
+/// Exchange rate of a single currency relative to the base currency
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct CurrencyRate {
+    /// Currency code
+    pub code: String,
+    /// Exchange rate relative to the base currency or `None` if the api returned
+    /// `false` or `null` instead of a number
+    #[serde(deserialize_with = "deserialize_rate_value")]
+    pub value: Option<f64>,
+    /// ISO-8601 timestamp the rate is effective at, if the api provides it per currency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<String>,
+}
+
+/// Reads the `value` of a rate, treating the `false` or `null` the api sometimes returns
+/// instead of a number as a missing rate instead of failing the whole response.
+fn deserialize_rate_value<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<f64>, D::Error> {
+    deserializer.deserialize_any(RateValueVisitor)
+}
+
+/// Visits a rate value that is either a number, `false` or `null`.
+struct RateValueVisitor;
+
+impl<'de> Visitor<'de> for RateValueVisitor {
+    type Value = Option<f64>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number, false or null")
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(Some(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(Some(value as f64))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(Some(value as f64))
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+        if value {
+            return Err(E::invalid_value(Unexpected::Bool(value), &self));
+        }
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+/// Meta information returned alongside the rates
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
+pub struct Meta {
+    /// ISO-8601 timestamp of the last rate update
+    pub last_updated_at: Option<String>,
+    /// Base currency the rates were requested for. The api does not return it,
+    /// so it is only set on rates fetched through the client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_currency: Option<String>,
+    /// Base currency the rates were actually requested with if the requested base was not
+    /// supported and the rates were rebased from the
+    /// [base_fallback](crate::api::CurrencyapiBuilder::base_fallback)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_base: Option<String>,
+}
+
+impl Meta {
+    /// Parses `last_updated_at`, returning `None` if it is missing or not a valid
+    /// ISO-8601 timestamp.
+    #[cfg(feature = "chrono")]
+    pub fn last_updated(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let timestamp = self.last_updated_at.as_deref()?;
+        chrono::DateTime::parse_from_rfc3339(timestamp)
+            .ok()
+            .map(|timestamp| timestamp.to_utc())
+    }
+}
+
+/// Change of a single rate between two tables, see [LatestResponse::top_movers]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Mover {
+    /// Currency code
+    pub code: String,
+    /// Rate in the baseline table
+    pub from: f64,
+    /// Rate in the current table
+    pub to: f64,
+    /// Change from `from` to `to` in percent
+    pub change_percent: f64,
+}
+
+/// Change of the rate of a currency pair between two dates, see [crate::Currencyapi::change]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ChangeResult {
+    /// Rate on the earlier date
+    pub from_rate: f64,
+    /// Rate on the later date
+    pub to_rate: f64,
+    /// Difference `to_rate - from_rate`
+    pub absolute: f64,
+    /// Change from `from_rate` to `to_rate` in percent
+    pub percentage: f64,
+}
+
+/// Response of the `latest` endpoint
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct LatestResponse {
+    /// Exchange rates keyed by currency code
+    pub data: HashMap<String, CurrencyRate>,
+    /// Meta information of the response
+    pub meta: Option<Meta>,
+}
+
+impl LatestResponse {
+    /// Converts `amount` from one currency of the table into another using the cross rate
+    /// of both currencies relative to the base currency.
+    ///
+    /// Returns `None` if either currency or its rate is missing or the rate of `from` is zero.
+    pub fn convert(&self, from: &str, to: &str, amount: f64) -> Option<f64> {
+        let from_rate = self.data.get(from)?.value?;
+        let to_rate = self.data.get(to)?.value?;
+        if from_rate == 0.0 {
+            return None;
+        }
+        Some(amount / from_rate * to_rate)
+    }
+
+    /// Merges several responses, e.g. of requests for chunks of a currency list, into one.
+    ///
+    /// The rates are combined into one table and the meta with the most recent
+    /// `last_updated_at` is kept. An empty list results in an empty table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [CurrencyapiError::BaseCurrencyMismatch] if the responses declare different
+    /// base currencies, responses without a base currency are not checked.
+    /// Returns [CurrencyapiError::MergeConflict] if a currency appears in several responses
+    /// with different rates, which indicates different snapshots.
+    pub fn merge(responses: Vec<LatestResponse>) -> Result<LatestResponse, CurrencyapiError> {
+        let mut merged = LatestResponse {
+            data: HashMap::new(),
+            meta: None,
+        };
+        let mut base: Option<String> = None;
+        for response in responses {
+            let response_base = response
+                .meta
+                .as_ref()
+                .and_then(|meta| meta.base_currency.clone());
+            match (&base, response_base) {
+                (Some(first), Some(second)) if !first.eq_ignore_ascii_case(&second) => {
+                    return Err(CurrencyapiError::BaseCurrencyMismatch {
+                        first: first.clone(),
+                        second,
+                    });
+                }
+                (None, Some(second)) => base = Some(second),
+                _ => {}
+            }
+            for (code, rate) in response.data {
+                match merged.data.get(&code) {
+                    Some(existing) if existing.value != rate.value => {
+                        return Err(CurrencyapiError::MergeConflict { code });
+                    }
+                    Some(_) => {}
+                    None => {
+                        merged.data.insert(code, rate);
+                    }
+                }
+            }
+            let updated_at =
+                |meta: &Option<Meta>| meta.as_ref().and_then(|meta| meta.last_updated_at.clone());
+            if merged.meta.is_none() || updated_at(&response.meta) > updated_at(&merged.meta) {
+                merged.meta = response.meta;
+            }
+        }
+        if let Some(meta) = merged.meta.as_mut() {
+            meta.base_currency = meta.base_currency.take().or(base);
+        }
+        Ok(merged)
+    }
+
+    /// Checks that the base currency is contained in the table with a rate of 1.0.
+    ///
+    /// # Errors
+    ///
+    /// Returns [CurrencyapiError::InvalidResponseData] if the base currency is unknown,
+    /// missing from the table, has no rate or has a rate that differs from 1.0 by more than
+    /// `f64::EPSILON`.
+    pub fn validate_base_identity(&self) -> Result<(), CurrencyapiError> {
+        let invalid = |reason: String| Err(CurrencyapiError::InvalidResponseData { reason });
+        let Some(base) = self
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.base_currency.as_deref())
+        else {
+            return invalid(String::from("base currency is unknown"));
+        };
+        match self.data.get(base).map(|rate| rate.value) {
+            Some(Some(rate)) if (rate - 1.0).abs() <= f64::EPSILON => Ok(()),
+            Some(Some(rate)) => invalid(format!(
+                "base currency '{}' has rate {} instead of 1",
+                base, rate
+            )),
+            Some(None) => invalid(format!("base currency '{}' has no rate", base)),
+            None => invalid(format!("base currency '{}' is missing", base)),
+        }
+    }
+
+    /// Returns the rate of each code in `codes` in the same order, or `None`
+    /// for codes missing from the table or without a rate.
+    pub fn values_for(&self, codes: &[&str]) -> Vec<Option<f64>> {
+        codes
+            .iter()
+            .map(|code| self.data.get(*code).and_then(|rate| rate.value))
+            .collect()
+    }
+
+    /// Returns the rates of all currencies whose rate lies within `[min, max]`, e.g. to
+    /// screen for currencies near parity. Missing and non-finite rates are never included.
+    pub fn filter_by_rate(&self, min: f64, max: f64) -> HashMap<String, f64> {
+        self.data
+            .iter()
+            .filter_map(|(code, rate)| Some((code.clone(), rate.value?)))
+            .filter(|(_, rate)| rate.is_finite() && (min..=max).contains(rate))
+            .collect()
+    }
+
+    /// Converts a table relative to `old_base` into one relative to `new_base`, which
+    /// has to be contained in the table with a non-zero rate. `old_base` is added to
+    /// the table with the inverse rate of `new_base`.
+    pub(crate) fn rebase(&self, old_base: &str, new_base: &str) -> Option<LatestResponse> {
+        let new_rate = self.data.get(new_base)?.value?;
+        if new_rate == 0.0 {
+            return None;
+        }
+        let mut data: HashMap<String, CurrencyRate> = self
+            .data
+            .iter()
+            .map(|(code, rate)| {
+                let mut rate = rate.clone();
+                rate.value = rate.value.map(|value| value / new_rate);
+                (code.clone(), rate)
+            })
+            .collect();
+        data.entry(String::from(old_base))
+            .or_insert_with(|| CurrencyRate {
+                code: String::from(old_base),
+                value: Some(1.0 / new_rate),
+                as_of: None,
+            });
+        Some(LatestResponse {
+            data,
+            meta: self.meta.clone(),
+        })
+    }
+
+    /// Returns the percentage deviation of each rate from the rate of the same currency
+    /// in `baseline`.
+    ///
+    /// Currencies missing from either table, without a rate or with a baseline rate of zero
+    /// are excluded.
+    pub fn deviation_from(&self, baseline: &LatestResponse) -> HashMap<String, f64> {
+        self.data
+            .iter()
+            .filter_map(|(code, rate)| {
+                let baseline_rate = baseline.data.get(code)?.value?;
+                if baseline_rate == 0.0 {
+                    return None;
+                }
+                let deviation = (rate.value? - baseline_rate) / baseline_rate * 100.0;
+                Some((code.clone(), deviation))
+            })
+            .collect()
+    }
+
+    /// Returns the `limit` currencies whose rates changed the most relative to `baseline`,
+    /// ordered by absolute percentage change with the largest first.
+    ///
+    /// Currencies missing from either table, without a rate or with a baseline rate of zero
+    /// are excluded.
+    pub fn top_movers(&self, baseline: &LatestResponse, limit: usize) -> Vec<Mover> {
+        let mut movers: Vec<Mover> = self
+            .deviation_from(baseline)
+            .into_iter()
+            .filter_map(|(code, change_percent)| {
+                Some(Mover {
+                    from: baseline.data[&code].value?,
+                    to: self.data[&code].value?,
+                    code,
+                    change_percent,
+                })
+            })
+            .collect();
+        movers.sort_by(|a, b| {
+            b.change_percent
+                .abs()
+                .total_cmp(&a.change_percent.abs())
+                .then_with(|| a.code.cmp(&b.code))
+        });
+        movers.truncate(limit);
+        movers
+    }
+
+    /// Converts `amount` from `from` into `to` and back again and returns the absolute
+    /// difference to the original amount.
+    ///
+    /// Returns `None` if either currency is missing from the table.
+    pub fn roundtrip_error(&self, from: &str, to: &str, amount: f64) -> Option<f64> {
+        let converted = self.convert(from, to, amount)?;
+        let roundtrip = self.convert(to, from, converted)?;
+        Some((amount - roundtrip).abs())
+    }
+
+    /// Reshapes the table into the response format of fixer.io, e.g. for consumers
+    /// migrated from fixer.io. The fields are mapped as follows:
+    ///
+    /// * `success` - Always `true`
+    /// * `timestamp` - `last_updated_at` of the meta as unix timestamp or `null` if it is
+    ///   missing, invalid or the `chrono` feature is disabled
+    /// * `base` - `base_currency` of the meta or `null` if it is unknown
+    /// * `date` - Date part of `last_updated_at` of the meta or `null` if it is missing
+    /// * `rates` - Rate of each currency keyed by its code or `null` if the rate is missing
+    pub fn to_fixer_json(&self) -> serde_json::Value {
+        let meta = self.meta.as_ref();
+        let last_updated_at = meta.and_then(|meta| meta.last_updated_at.as_deref());
+        let rates: serde_json::Map<String, serde_json::Value> = self
+            .data
+            .iter()
+            .map(|(code, rate)| (code.clone(), serde_json::Value::from(rate.value)))
+            .collect();
+        #[cfg(feature = "chrono")]
+        let timestamp = self
+            .last_updated_at()
+            .map(|timestamp| timestamp.timestamp());
+        #[cfg(not(feature = "chrono"))]
+        let timestamp: Option<i64> = None;
+        serde_json::json!({
+            "success": true,
+            "timestamp": timestamp,
+            "base": meta.and_then(|meta| meta.base_currency.clone()),
+            "date": last_updated_at.and_then(|timestamp| timestamp.get(..10)),
+            "rates": rates,
+        })
+    }
+
+    /// Returns the time the rates were last updated or `None` if the meta or its
+    /// `last_updated_at` timestamp is missing or invalid.
+    #[cfg(feature = "chrono")]
+    pub fn last_updated_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.meta.as_ref().and_then(Meta::last_updated)
+    }
+
+    /// Returns `true` if the rates were last updated more than `max_age` before `now`.
+    ///
+    /// A missing or unparsable `last_updated_at` timestamp is treated as stale.
+    #[cfg(feature = "chrono")]
+    pub fn is_stale(
+        &self,
+        max_age: std::time::Duration,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        let Some(last_updated_at) = self.last_updated_at() else {
+            return true;
+        };
+        let Ok(max_age) = chrono::TimeDelta::from_std(max_age) else {
+            return false;
+        };
+        now.signed_duration_since(last_updated_at) > max_age
+    }
+}
+
+/// Returns the sorted currency codes present in both responses.
+pub fn common_codes(a: &LatestResponse, b: &LatestResponse) -> BTreeSet<String> {
+    a.data
+        .keys()
+        .filter(|code| b.data.contains_key(*code))
+        .cloned()
+        .collect()
+}
+
+/// Parses a `latest` response body and passes each currency code and rate to `sink`
+/// without collecting the rates into a map. Currencies without a rate are skipped.
+pub(crate) fn for_each_rate<F: FnMut(&str, f64)>(
+    body: &str,
+    sink: F,
+) -> Result<(), serde_json::Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(body);
+    ResponseSeed(sink).deserialize(&mut deserializer)?;
+    deserializer.end()
+}
+
+/// Visits the top level of a response and forwards the `data` map to [DataSeed].
+struct ResponseSeed<F>(F);
+
+impl<'de, F: FnMut(&str, f64)> DeserializeSeed<'de> for ResponseSeed<F> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, F: FnMut(&str, f64)> Visitor<'de> for ResponseSeed<F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a latest response object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "data" {
+                map.next_value_seed(DataSeed(&mut self.0))?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Visits the `data` map and calls the sink for every entry.
+struct DataSeed<'s, F>(&'s mut F);
+
+impl<'de, F: FnMut(&str, f64)> DeserializeSeed<'de> for DataSeed<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, F: FnMut(&str, f64)> Visitor<'de> for DataSeed<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of currency rates")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some((code, rate)) = map.next_entry::<String, CurrencyRate>()? {
+            if let Some(value) = rate.value {
+                (self.0)(&code, value);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod latest_test {
+    use super::*;
+
+    pub(crate) fn table(rates: &[(&str, f64)]) -> LatestResponse {
+        LatestResponse {
+            data: rates
+                .iter()
+                .map(|(code, value)| {
+                    let rate = CurrencyRate {
+                        code: String::from(*code),
+                        value: Some(*value),
+                        as_of: None,
+                    };
+                    (String::from(*code), rate)
+                })
+                .collect(),
+            meta: None,
+        }
+    }
+
+    /// Body of a `/latest` response as returned by the api
+    const LATEST_BODY: &str = r#"{
+        "meta": {"last_updated_at": "2023-06-23T10:15:59Z"},
+        "data": {
+            "AED": {"code": "AED", "value": 3.67306},
+            "AFN": {"code": "AFN", "value": 91.80254},
+            "ALL": {"code": "ALL", "value": 108.22904},
+            "AMD": {"code": "AMD", "value": 480.41659}
+        }
+    }"#;
+
+    #[test]
+    fn should_parse_latest_response_of_api() {
+        let res: LatestResponse = serde_json::from_str(LATEST_BODY).unwrap();
+        assert_eq!(res.data.len(), 4);
+        assert_eq!(
+            res.data["AFN"],
+            CurrencyRate {
+                code: String::from("AFN"),
+                value: Some(91.80254),
+                as_of: None,
+            }
+        );
+        let meta = res.meta.unwrap();
+        assert_eq!(
+            meta.last_updated_at.as_deref(),
+            Some("2023-06-23T10:15:59Z")
+        );
+        assert_eq!(meta.base_currency, None);
+    }
+
+    #[test]
+    fn should_parse_false_and_null_rates_as_missing() {
+        let body = r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":0.9},"VES":{"code":"VES","value":false},"ZWL":{"code":"ZWL","value":null},"JPY":{"code":"JPY","value":148}}}"#;
+        let res: LatestResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(res.data["EUR"].value, Some(0.9));
+        assert_eq!(res.data["JPY"].value, Some(148.0));
+        assert_eq!(res.data["VES"].value, None);
+        assert_eq!(res.data["ZWL"].value, None);
+        assert_eq!(res.convert("EUR", "VES", 1.0), None);
+
+        let body = r#"{"meta":null,"data":{"EUR":{"code":"EUR","value":true}}}"#;
+        assert!(serde_json::from_str::<LatestResponse>(body).is_err());
+    }
+
+    #[test]
+    fn should_roundtrip_with_near_zero_error() {
+        let res = table(&[("USD", 1.0), ("EUR", 0.92), ("JPY", 149.73)]);
+        let error = res.roundtrip_error("EUR", "JPY", 1234.56).unwrap();
+        assert!(error < 1e-9);
+    }
+
+    #[test]
+    fn should_not_roundtrip_missing_codes() {
+        let res = table(&[("USD", 1.0), ("EUR", 0.92)]);
+        assert_eq!(res.roundtrip_error("USD", "GBP", 10.0), None);
+        assert_eq!(res.roundtrip_error("GBP", "USD", 10.0), None);
+    }
+
+    #[test]
+    fn should_reshape_table_into_fixer_format() {
+        let mut res = table(&[("EUR", 0.92), ("JPY", 149.73)]);
+        res.meta = Some(Meta {
+            last_updated_at: Some(String::from("2024-03-01T23:59:59Z")),
+            base_currency: Some(String::from("USD")),
+            ..Meta::default()
+        });
+        let timestamp = cfg!(feature = "chrono").then_some(1709337599);
+        let expected = serde_json::json!({
+            "success": true,
+            "timestamp": timestamp,
+            "base": "USD",
+            "date": "2024-03-01",
+            "rates": {"EUR": 0.92, "JPY": 149.73}
+        });
+        assert_eq!(res.to_fixer_json(), expected);
+        let fixer = table(&[]).to_fixer_json();
+        assert_eq!(fixer["timestamp"], serde_json::Value::Null);
+        assert_eq!(fixer["base"], serde_json::Value::Null);
+        assert_eq!(fixer["date"], serde_json::Value::Null);
+        assert_eq!(fixer["rates"], serde_json::json!({}));
+    }
+
+    #[test]
+    fn should_return_values_in_requested_order() {
+        let res = table(&[("USD", 1.0), ("EUR", 0.92), ("JPY", 149.73)]);
+        assert_eq!(
+            res.values_for(&["JPY", "GBP", "USD", "EUR"]),
+            vec![Some(149.73), None, Some(1.0), Some(0.92)]
+        );
+    }
+
+    #[test]
+    fn should_filter_rates_near_parity() {
+        let res = table(&[
+            ("USD", 1.0),
+            ("EUR", 0.92),
+            ("GBP", 0.79),
+            ("CHF", 1.1),
+            ("JPY", 149.73),
+            ("XXX", f64::NAN),
+        ]);
+        let filtered = res.filter_by_rate(0.9, 1.1);
+        assert_eq!(
+            filtered,
+            HashMap::from([
+                (String::from("USD"), 1.0),
+                (String::from("EUR"), 0.92),
+                (String::from("CHF"), 1.1)
+            ])
+        );
+        assert_eq!(
+            res.filter_by_rate(f64::NEG_INFINITY, f64::INFINITY).len(),
+            5
+        );
+        assert!(res.filter_by_rate(2.0, 1.0).is_empty());
+    }
+
+    #[test]
+    fn should_compute_deviation_from_baseline() {
+        let latest = table(&[("EUR", 0.99), ("GBP", 0.8), ("JPY", 150.0)]);
+        let baseline = table(&[("EUR", 0.9), ("GBP", 0.8), ("CHF", 0.88)]);
+        let deviation = latest.deviation_from(&baseline);
+        assert_eq!(deviation.len(), 2);
+        assert!((deviation["EUR"] - 10.0).abs() < 1e-9);
+        assert_eq!(deviation["GBP"], 0.0);
+    }
+
+    #[test]
+    fn should_return_sorted_common_codes() {
+        let a = table(&[("USD", 1.0), ("JPY", 149.73), ("EUR", 0.92), ("CHF", 0.88)]);
+        let b = table(&[("GBP", 0.79), ("EUR", 0.93), ("USD", 1.0), ("JPY", 150.1)]);
+        let common: Vec<String> = common_codes(&a, &b).into_iter().collect();
+        assert_eq!(common, vec!["EUR", "JPY", "USD"]);
+    }
+
+    #[test]
+    fn should_rebase_table_onto_contained_currency() {
+        let usd = table(&[("EUR", 0.9), ("GBP", 0.8), ("JPY", 150.0)]);
+        let eur = usd.rebase("USD", "EUR").unwrap();
+        assert_eq!(eur.data["EUR"].value, Some(1.0));
+        assert!((eur.data["GBP"].value.unwrap() - 0.8 / 0.9).abs() < 1e-12);
+        assert!((eur.data["USD"].value.unwrap() - 1.0 / 0.9).abs() < 1e-12);
+        assert!(usd.rebase("USD", "CHF").is_none());
+    }
+
+    #[test]
+    fn should_rank_movers_by_absolute_change() {
+        let baseline = table(&[("EUR", 0.9), ("GBP", 0.8), ("JPY", 150.0), ("CHF", 0.0)]);
+        let latest = table(&[("EUR", 0.99), ("GBP", 0.76), ("JPY", 151.5), ("CHF", 0.9)]);
+        let movers = latest.top_movers(&baseline, 2);
+        let codes: Vec<&str> = movers.iter().map(|mover| mover.code.as_str()).collect();
+        assert_eq!(codes, ["EUR", "GBP"]);
+        assert_eq!(movers[0].from, 0.9);
+        assert_eq!(movers[0].to, 0.99);
+        assert!((movers[0].change_percent - 10.0).abs() < 1e-9);
+        assert!((movers[1].change_percent + 5.0).abs() < 1e-9);
+    }
+
+    fn with_base(mut res: LatestResponse, base: &str) -> LatestResponse {
+        res.meta = Some(Meta {
+            base_currency: Some(String::from(base)),
+            ..Meta::default()
+        });
+        res
+    }
+
+    #[test]
+    fn should_accept_base_with_identity_rate() {
+        let res = with_base(table(&[("USD", 1.0), ("EUR", 0.92)]), "USD");
+        assert!(res.validate_base_identity().is_ok());
+    }
+
+    #[test]
+    fn should_reject_base_with_wrong_rate() {
+        let res = with_base(table(&[("USD", 1.02), ("EUR", 0.92)]), "USD");
+        let err = res.validate_base_identity().unwrap_err();
+        assert!(matches!(err, CurrencyapiError::InvalidResponseData { .. }));
+    }
+
+    #[test]
+    fn should_reject_missing_or_unknown_base() {
+        let missing = with_base(table(&[("EUR", 0.92)]), "USD");
+        assert!(missing.validate_base_identity().is_err());
+        assert!(table(&[("USD", 1.0)]).validate_base_identity().is_err());
+    }
+
+    fn with_timestamp(mut res: LatestResponse, last_updated_at: &str) -> LatestResponse {
+        res.meta = Some(Meta {
+            last_updated_at: Some(String::from(last_updated_at)),
+            ..Meta::default()
+        });
+        res
+    }
+
+    #[test]
+    fn should_merge_partial_responses() {
+        let first = with_timestamp(
+            table(&[("EUR", 0.92), ("GBP", 0.79)]),
+            "2024-01-01T12:00:00Z",
+        );
+        let second = with_timestamp(
+            table(&[("JPY", 149.73), ("EUR", 0.92)]),
+            "2024-01-01T12:00:59Z",
+        );
+        let merged = LatestResponse::merge(vec![first, second]).unwrap();
+        assert_eq!(
+            merged.values_for(&["EUR", "GBP", "JPY"]),
+            vec![Some(0.92), Some(0.79), Some(149.73)]
+        );
+        assert_eq!(
+            merged.meta.unwrap().last_updated_at.as_deref(),
+            Some("2024-01-01T12:00:59Z")
+        );
+    }
+
+    #[test]
+    fn should_reject_conflicting_rates() {
+        let first = table(&[("EUR", 0.92)]);
+        let second = table(&[("EUR", 0.95)]);
+        let err = LatestResponse::merge(vec![first, second]).unwrap_err();
+        assert!(matches!(err, CurrencyapiError::MergeConflict { code } if code == "EUR"));
+    }
+
+    #[test]
+    fn should_reject_merging_different_base_currencies() {
+        let first = with_base(table(&[("EUR", 0.92)]), "USD");
+        let second = with_base(table(&[("JPY", 161.2)]), "EUR");
+        let err = LatestResponse::merge(vec![first, second]).unwrap_err();
+        assert!(matches!(
+            err,
+            CurrencyapiError::BaseCurrencyMismatch { first, second }
+                if first == "USD" && second == "EUR"
+        ));
+
+        let first = with_base(table(&[("EUR", 0.92)]), "USD");
+        let second = with_timestamp(table(&[("GBP", 0.79)]), "2024-01-01T12:00:59Z");
+        let merged = LatestResponse::merge(vec![first, second]).unwrap();
+        assert_eq!(merged.meta.unwrap().base_currency.as_deref(), Some("USD"));
+    }
+
+    #[test]
+    fn should_call_sink_once_per_currency() {
+        let body = r#"{
+            "meta": {"last_updated_at": "2023-06-23T10:15:59Z"},
+            "data": {
+                "EUR": {"code": "EUR", "value": 0.92},
+                "GBP": {"code": "GBP", "value": 0.79},
+                "JPY": {"code": "JPY", "value": 149.73}
+            }
+        }"#;
+        let mut rates = Vec::new();
+        for_each_rate(body, |code, value| rates.push((String::from(code), value))).unwrap();
+        rates.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            rates,
+            vec![
+                (String::from("EUR"), 0.92),
+                (String::from("GBP"), 0.79),
+                (String::from("JPY"), 149.73)
+            ]
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    mod stale_test {
+        use super::*;
+        use chrono::{TimeZone, Utc};
+        use std::time::Duration;
+
+        fn response(last_updated_at: Option<&str>) -> LatestResponse {
+            LatestResponse {
+                data: HashMap::new(),
+                meta: Some(Meta {
+                    last_updated_at: last_updated_at.map(String::from),
+                    ..Meta::default()
+                }),
+            }
+        }
+
+        #[test]
+        fn should_not_be_stale_within_max_age() {
+            let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 5, 0).unwrap();
+            let res = response(Some("2024-01-01T12:00:00Z"));
+            assert!(!res.is_stale(Duration::from_secs(10 * 60), now));
+        }
+
+        #[test]
+        fn should_be_stale_past_max_age() {
+            let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 30, 0).unwrap();
+            let res = response(Some("2024-01-01T12:00:00Z"));
+            assert!(res.is_stale(Duration::from_secs(10 * 60), now));
+        }
+
+        #[test]
+        fn should_parse_last_updated_at() {
+            let res = response(Some("2024-01-01T13:00:00+01:00"));
+            assert_eq!(
+                res.last_updated_at(),
+                Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap())
+            );
+            assert_eq!(response(Some("yesterday")).last_updated_at(), None);
+            assert_eq!(response(None).last_updated_at(), None);
+        }
+
+        #[test]
+        fn should_be_stale_without_timestamp() {
+            let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+            assert!(response(None).is_stale(Duration::from_secs(60), now));
+            let without_meta = LatestResponse {
+                data: HashMap::new(),
+                meta: None,
+            };
+            assert!(without_meta.is_stale(Duration::from_secs(60), now));
+        }
+    }
+}
@@ -1,6 +1,32 @@
 use std::collections::HashMap;
 use serde_json::Value;
 
+pub mod convert;
+pub mod currencies;
+#[cfg(feature = "decimal")]
+pub mod decimal;
+pub mod fixed;
+pub mod latest;
+pub mod range;
+#[cfg(feature = "bincode")]
+mod snapshot;
+pub mod status;
+
+pub use convert::{AveragedConversion, ConversionResult};
+#[cfg(feature = "chrono")]
+pub use convert::{DatedConversion, RateSource};
+pub use currencies::{
+    CurrenciesResponse, CurrencyMetadata, CurrencyType, FieldChange, MetadataDiff,
+};
+#[cfg(feature = "decimal")]
+pub use decimal::{CurrencyRateDecimal, LatestResponseDecimal};
+pub use fixed::FixedRates;
+pub use latest::{common_codes, ChangeResult, CurrencyRate, LatestResponse, Meta, Mover};
+#[cfg(feature = "chrono")]
+pub use range::TimeSeries;
+pub use range::{RangeAccuracy, RangeResponse, RateAverage};
+pub use status::{Quota, Quotas, StatusResponse};
+
 /// Response of the currencyapi
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
 pub struct DetailsResponse {
@@ -8,4 +34,4 @@ pub struct DetailsResponse {
     pub data: HashMap<String, Value>,
     /// Request status
     pub meta: Option<HashMap<String, Value>>,
-}
\ No newline at end of file
+}
@@ -0,0 +1,318 @@
+//! Typed response of the `range` endpoint
+
+use crate::models::CurrencyRate;
+use serde::{Deserialize, Deserializer, Serializer};
+use std::collections::{BTreeMap, HashMap};
+
+/// Rates of all currencies keyed by currency code
+pub type RateTable = HashMap<String, CurrencyRate>;
+
+/// Response of the `range` endpoint
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct RangeResponse {
+    /// Rates keyed by their ISO-8601 datetime, ordered chronologically
+    #[serde(
+        deserialize_with = "deserialize_entries",
+        serialize_with = "serialize_entries"
+    )]
+    pub data: BTreeMap<String, RateTable>,
+}
+
+/// Rates of a single datetime as returned by the api
+#[derive(Deserialize, Serialize)]
+struct RangeEntry<D, C> {
+    datetime: D,
+    currencies: C,
+}
+
+/// Reads the list of datetime entries of the api into a map keyed by datetime.
+fn deserialize_entries<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, RateTable>, D::Error> {
+    let entries: Vec<RangeEntry<String, RateTable>> = Deserialize::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| (entry.datetime, entry.currencies))
+        .collect())
+}
+
+/// Writes the map back as the list of datetime entries used by the api.
+fn serialize_entries<S: Serializer>(
+    data: &BTreeMap<String, RateTable>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(data.iter().map(|(datetime, currencies)| RangeEntry {
+        datetime,
+        currencies,
+    }))
+}
+
+/// How the rates of a currency over a range are combined into a single rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateAverage {
+    /// Arithmetic mean of the rates
+    #[default]
+    Mean,
+    /// Median of the rates, i.e. the mean of both middle rates for an even count
+    Median,
+}
+
+/// Interval between the rates of a `range` request, converted from and to the strings
+/// of the api like `quarter_hour`
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    strum::Display,
+    strum::EnumString,
+    strum::AsRefStr,
+    strum::IntoStaticStr,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum RangeAccuracy {
+    /// One rate per day
+    #[default]
+    Day,
+    /// One rate per hour
+    Hour,
+    /// One rate per 15 minutes
+    QuarterHour,
+    /// One rate per 5 minutes
+    FiveMinute,
+}
+
+impl RangeAccuracy {
+    /// Returns the name of the accuracy as used by the api.
+    pub fn as_str(self) -> &'static str {
+        self.into()
+    }
+}
+
+/// Rates of one currency over time, e.g. for charting
+#[cfg(feature = "chrono")]
+#[derive(Debug, PartialEq, Clone)]
+pub struct TimeSeries {
+    /// Currency code
+    pub currency: String,
+    /// Rates with their datetime, oldest first
+    pub points: Vec<(chrono::DateTime<chrono::Utc>, f64)>,
+}
+
+impl RangeResponse {
+    /// Combines the finite rates of `code` over all datetimes of the response into a
+    /// single rate with `average`. Datetimes without a rate for `code` are skipped.
+    ///
+    /// Returns the rate together with the number of rates it is based on, or `None` if
+    /// the response contains no rate for `code`.
+    pub fn average_rate(&self, code: &str, average: RateAverage) -> Option<(f64, usize)> {
+        let mut rates: Vec<f64> = self
+            .data
+            .values()
+            .filter_map(|rates| rates.get(code)?.value)
+            .filter(|value| value.is_finite())
+            .collect();
+        if rates.is_empty() {
+            return None;
+        }
+        let rate = match average {
+            RateAverage::Mean => rates.iter().sum::<f64>() / rates.len() as f64,
+            RateAverage::Median => {
+                rates.sort_by(f64::total_cmp);
+                let middle = rates.len() / 2;
+                if rates.len().is_multiple_of(2) {
+                    (rates[middle - 1] + rates[middle]) / 2.0
+                } else {
+                    rates[middle]
+                }
+            }
+        };
+        Some((rate, rates.len()))
+    }
+
+    /// Returns the rates of `code` with their datetime in the order of [RangeResponse::data],
+    /// e.g. to plot a single currency without sorting by hand. Datetimes without a rate for
+    /// `code` are skipped.
+    ///
+    /// The datetimes are ordered as strings, which is chronological for the uniformly
+    /// formatted timestamps of the api. Use [RangeResponse::time_series] to order by the
+    /// parsed timestamps instead.
+    pub fn series(&self, code: &str) -> Vec<(String, f64)> {
+        self.data
+            .iter()
+            .filter_map(|(datetime, rates)| Some((datetime.clone(), rates.get(code)?.value?)))
+            .collect()
+    }
+
+    /// Returns the datetimes contained in the response in chronological order, e.g. to
+    /// detect gaps of a range requested with a coarse accuracy.
+    ///
+    /// Entries with a datetime that is not a valid RFC 3339 timestamp are skipped.
+    #[cfg(feature = "chrono")]
+    pub fn returned_dates(&self) -> Vec<chrono::DateTime<chrono::Utc>> {
+        let mut dates: Vec<_> = self
+            .data
+            .keys()
+            .filter_map(|datetime| chrono::DateTime::parse_from_rfc3339(datetime).ok())
+            .map(|datetime| datetime.to_utc())
+            .collect();
+        dates.sort();
+        dates
+    }
+
+    /// Transposes the response into one [TimeSeries] per currency, ordered by currency code.
+    ///
+    /// Entries with a datetime that is not a valid RFC 3339 timestamp and missing rates
+    /// are skipped.
+    #[cfg(feature = "chrono")]
+    pub fn time_series(&self) -> Vec<TimeSeries> {
+        let mut series: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+        for (datetime, rates) in &self.data {
+            let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(datetime) else {
+                continue;
+            };
+            for (code, rate) in rates {
+                let Some(value) = rate.value else {
+                    continue;
+                };
+                series
+                    .entry(code)
+                    .or_default()
+                    .push((datetime.to_utc(), value));
+            }
+        }
+        series
+            .into_iter()
+            .map(|(currency, mut points)| {
+                points.sort_by_key(|(datetime, _)| *datetime);
+                TimeSeries {
+                    currency: String::from(currency),
+                    points,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod range_test {
+    use super::*;
+
+    const BODY: &str = r#"{"data":[
+        {"datetime":"2024-01-02T23:59:59Z","currencies":{"EUR":{"code":"EUR","value":0.91},"GBP":{"code":"GBP","value":0.79}}},
+        {"datetime":"2024-01-01T23:59:59Z","currencies":{"EUR":{"code":"EUR","value":0.9},"GBP":{"code":"GBP","value":0.78}}}
+    ]}"#;
+
+    #[test]
+    fn should_convert_range_accuracy_from_and_to_strings() {
+        assert_eq!(RangeAccuracy::QuarterHour.as_str(), "quarter_hour");
+        assert_eq!(RangeAccuracy::FiveMinute.to_string(), "five_minute");
+        assert_eq!("hour".parse::<RangeAccuracy>(), Ok(RangeAccuracy::Hour));
+        assert!("daily".parse::<RangeAccuracy>().is_err());
+    }
+
+    #[test]
+    fn should_key_entries_by_datetime() {
+        let res: RangeResponse = serde_json::from_str(BODY).unwrap();
+        let datetimes: Vec<&String> = res.data.keys().collect();
+        assert_eq!(datetimes, ["2024-01-01T23:59:59Z", "2024-01-02T23:59:59Z"]);
+        assert_eq!(res.data["2024-01-02T23:59:59Z"]["GBP"].value, Some(0.79));
+    }
+
+    #[test]
+    fn should_serialize_entries_as_list() {
+        let res: RangeResponse = serde_json::from_str(BODY).unwrap();
+        let json = serde_json::to_value(&res).unwrap();
+        assert_eq!(json["data"][0]["datetime"], "2024-01-01T23:59:59Z");
+        let roundtrip: RangeResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(roundtrip, res);
+    }
+
+    #[test]
+    fn should_return_ordered_series_of_one_currency() {
+        let body = r#"{"data":[
+            {"datetime":"2024-01-03T23:59:59Z","currencies":{"EUR":{"code":"EUR","value":0.92}}},
+            {"datetime":"2024-01-01T23:59:59Z","currencies":{"EUR":{"code":"EUR","value":0.9}}},
+            {"datetime":"2024-01-02T23:59:59Z","currencies":{"GBP":{"code":"GBP","value":0.79}}}
+        ]}"#;
+        let res: RangeResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(
+            res.series("EUR"),
+            [
+                (String::from("2024-01-01T23:59:59Z"), 0.9),
+                (String::from("2024-01-03T23:59:59Z"), 0.92),
+            ]
+        );
+        assert!(res.series("USD").is_empty());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_transpose_range_into_time_series() {
+        use chrono::{TimeZone, Utc};
+
+        let res: RangeResponse = serde_json::from_str(BODY).unwrap();
+        let day = |d| Utc.with_ymd_and_hms(2024, 1, d, 23, 59, 59).unwrap();
+        assert_eq!(
+            res.time_series(),
+            [
+                TimeSeries {
+                    currency: String::from("EUR"),
+                    points: vec![(day(1), 0.9), (day(2), 0.91)],
+                },
+                TimeSeries {
+                    currency: String::from("GBP"),
+                    points: vec![(day(1), 0.78), (day(2), 0.79)],
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_return_sorted_dates_of_entries() {
+        use chrono::{TimeZone, Utc};
+
+        let body = r#"{"data":[
+            {"datetime":"2024-01-08T00:00:00+02:00","currencies":{}},
+            {"datetime":"2024-01-01T23:59:59Z","currencies":{}},
+            {"datetime":"2024-01-07T23:00:00Z","currencies":{}},
+            {"datetime":"not a date","currencies":{}}
+        ]}"#;
+        let res: RangeResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(
+            res.returned_dates(),
+            [
+                Utc.with_ymd_and_hms(2024, 1, 1, 23, 59, 59).unwrap(),
+                Utc.with_ymd_and_hms(2024, 1, 7, 22, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 1, 7, 23, 0, 0).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_average_rates_of_present_days() {
+        let body = r#"{"data":[
+            {"datetime":"2024-01-01T23:59:59Z","currencies":{"EUR":{"code":"EUR","value":0.9}}},
+            {"datetime":"2024-01-02T23:59:59Z","currencies":{"GBP":{"code":"GBP","value":0.79}}},
+            {"datetime":"2024-01-03T23:59:59Z","currencies":{"EUR":{"code":"EUR","value":0.96}}},
+            {"datetime":"2024-01-04T23:59:59Z","currencies":{"EUR":{"code":"EUR","value":0.91}}}
+        ]}"#;
+        let res: RangeResponse = serde_json::from_str(body).unwrap();
+        let (mean, samples) = res.average_rate("EUR", RateAverage::Mean).unwrap();
+        assert!((mean - 0.9233333333333333).abs() < 1e-12);
+        assert_eq!(samples, 3);
+        assert_eq!(
+            res.average_rate("EUR", RateAverage::Median),
+            Some((0.91, 3))
+        );
+        assert_eq!(
+            res.average_rate("GBP", RateAverage::Median),
+            Some((0.79, 1))
+        );
+        assert_eq!(res.average_rate("JPY", RateAverage::Mean), None);
+    }
+}
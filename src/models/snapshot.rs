@@ -0,0 +1,140 @@
+//! Compact binary snapshots of a [LatestResponse] based on bincode
+
+use crate::error::CurrencyapiError;
+use crate::models::{CurrencyRate, LatestResponse, Meta};
+use bincode::{Decode, Encode};
+
+/// Version of the snapshot layout, stored as first byte of every snapshot
+const SNAPSHOT_VERSION: u8 = 2;
+
+/// Binary layout of a [LatestResponse]. It is kept separate from the JSON
+/// representation, so skipped optional fields do not break the round trip.
+#[derive(Encode, Decode)]
+struct Snapshot {
+    rates: Vec<(String, SnapshotRate)>,
+    meta: Option<SnapshotMeta>,
+}
+
+#[derive(Encode, Decode)]
+struct SnapshotRate {
+    code: String,
+    value: Option<f64>,
+    as_of: Option<String>,
+}
+
+#[derive(Encode, Decode)]
+struct SnapshotMeta {
+    last_updated_at: Option<String>,
+    base_currency: Option<String>,
+    fallback_base: Option<String>,
+}
+
+impl LatestResponse {
+    /// Encodes the table into a compact binary snapshot, e.g. to persist the last
+    /// fetched rates and restore them with [LatestResponse::from_bytes] after a restart.
+    ///
+    /// # Errors
+    ///
+    /// Returns [CurrencyapiError::SnapshotError] if the table cannot be encoded.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CurrencyapiError> {
+        let snapshot = Snapshot {
+            rates: self
+                .data
+                .iter()
+                .map(|(key, rate)| {
+                    let rate = SnapshotRate {
+                        code: rate.code.clone(),
+                        value: rate.value,
+                        as_of: rate.as_of.clone(),
+                    };
+                    (key.clone(), rate)
+                })
+                .collect(),
+            meta: self.meta.as_ref().map(|meta| SnapshotMeta {
+                last_updated_at: meta.last_updated_at.clone(),
+                base_currency: meta.base_currency.clone(),
+                fallback_base: meta.fallback_base.clone(),
+            }),
+        };
+        let config = bincode::config::standard();
+        let mut bytes = vec![SNAPSHOT_VERSION];
+        bincode::encode_into_std_write(snapshot, &mut bytes, config).map_err(|err| {
+            CurrencyapiError::SnapshotError {
+                reason: err.to_string(),
+            }
+        })?;
+        Ok(bytes)
+    }
+
+    /// Decodes a table from a snapshot created with [LatestResponse::to_bytes].
+    ///
+    /// # Errors
+    ///
+    /// Returns [CurrencyapiError::SnapshotError] if `bytes` is not a complete snapshot
+    /// of the current version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<LatestResponse, CurrencyapiError> {
+        let invalid = |reason: String| CurrencyapiError::SnapshotError { reason };
+        let Some((&version, encoded)) = bytes.split_first() else {
+            return Err(invalid(String::from("snapshot is empty")));
+        };
+        if version != SNAPSHOT_VERSION {
+            return Err(invalid(format!("unsupported snapshot version {}", version)));
+        }
+        let (snapshot, read): (Snapshot, usize) =
+            bincode::decode_from_slice(encoded, bincode::config::standard())
+                .map_err(|err| invalid(err.to_string()))?;
+        if read != encoded.len() {
+            return Err(invalid(String::from("trailing bytes after snapshot")));
+        }
+        Ok(LatestResponse {
+            data: snapshot
+                .rates
+                .into_iter()
+                .map(|(key, rate)| {
+                    let rate = CurrencyRate {
+                        code: rate.code,
+                        value: rate.value,
+                        as_of: rate.as_of,
+                    };
+                    (key, rate)
+                })
+                .collect(),
+            meta: snapshot.meta.map(|meta| Meta {
+                last_updated_at: meta.last_updated_at,
+                base_currency: meta.base_currency,
+                fallback_base: meta.fallback_base,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod snapshot_test {
+    use super::*;
+
+    #[test]
+    fn should_round_trip_rates_and_meta() {
+        let mut table = crate::models::latest::latest_test::table(&[("EUR", 0.92), ("JPY", 149.7)]);
+        table.data.get_mut("EUR").unwrap().as_of = Some(String::from("2024-03-01T12:00:00Z"));
+        table.meta = Some(Meta {
+            last_updated_at: Some(String::from("2024-03-01T23:59:59Z")),
+            base_currency: Some(String::from("USD")),
+            ..Meta::default()
+        });
+        let bytes = table.to_bytes().unwrap();
+        assert_eq!(LatestResponse::from_bytes(&bytes).unwrap(), table);
+        assert!(bytes.len() < serde_json::to_vec(&table).unwrap().len());
+    }
+
+    #[test]
+    fn should_reject_truncated_and_foreign_snapshots() {
+        let table = crate::models::latest::latest_test::table(&[("EUR", 0.92)]);
+        let bytes = table.to_bytes().unwrap();
+        for invalid in [&[][..], &bytes[..bytes.len() - 1], &[2, 0]] {
+            assert!(matches!(
+                LatestResponse::from_bytes(invalid),
+                Err(CurrencyapiError::SnapshotError { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Typed response of the `status` endpoint
+
+/// Usage of a single request quota
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Quota {
+    /// Number of requests included in the quota
+    pub total: u64,
+    /// Number of requests used so far
+    pub used: u64,
+    /// Number of requests left
+    pub remaining: u64,
+}
+
+/// Request quotas of the api key
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Quotas {
+    /// Quota of the current month
+    pub month: Quota,
+    /// Grace quota usable once the monthly quota is used up, if the plan has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grace: Option<Quota>,
+}
+
+/// Response of the `status` endpoint
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Default)]
+pub struct StatusResponse {
+    /// Identifier of the account the api key belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<u64>,
+    /// Request quotas of the api key
+    pub quotas: Quotas,
+}
+
+impl StatusResponse {
+    /// Returns `true` if neither the monthly quota nor the grace quota has requests left.
+    pub fn is_exhausted(&self) -> bool {
+        let grace = self.quotas.grace.map_or(0, |grace| grace.remaining);
+        self.quotas.month.remaining == 0 && grace == 0
+    }
+}
+
+#[cfg(test)]
+mod status_test {
+    use super::*;
+
+    const BODY: &str = r#"{"account_id":313373,"quotas":{"month":{"total":300,"used":72,"remaining":228},"grace":{"total":0,"used":0,"remaining":0}}}"#;
+
+    #[test]
+    fn should_parse_status_response() {
+        let res: StatusResponse = serde_json::from_str(BODY).unwrap();
+        assert_eq!(res.account_id, Some(313373));
+        assert_eq!(
+            res.quotas.month,
+            Quota {
+                total: 300,
+                used: 72,
+                remaining: 228,
+            }
+        );
+        assert!(!res.is_exhausted());
+    }
+
+    #[test]
+    fn should_be_exhausted_without_monthly_and_grace_requests() {
+        let mut res: StatusResponse = serde_json::from_str(BODY).unwrap();
+        res.quotas.month.used = 300;
+        res.quotas.month.remaining = 0;
+        assert!(res.is_exhausted());
+        res.quotas.grace = Some(Quota {
+            total: 50,
+            used: 10,
+            remaining: 40,
+        });
+        assert!(!res.is_exhausted());
+    }
+}
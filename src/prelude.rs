@@ -0,0 +1,17 @@
+//! Re-exports of the types needed for most uses of the crate
+//!
+//! ```
+//! use currencyapi_rs::prelude::*;
+//!
+//! async fn euro_rate(c_api: &Currencyapi) -> Result<LatestResponse, Error> {
+//!     c_api.latest("USD", &["EUR"], Some(CurrencyType::Fiat)).await
+//! }
+//! ```
+
+pub use crate::api::{Currencyapi, CurrencyapiBuilder};
+pub use crate::error::CurrencyapiError as Error;
+pub use crate::models::{
+    ConversionResult, CurrenciesResponse, CurrencyRate, CurrencyType, DetailsResponse,
+    LatestResponse, Meta, RangeAccuracy, RangeResponse, StatusResponse,
+};
+pub use crate::retry::RetryConfig;
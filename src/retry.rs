@@ -0,0 +1,388 @@
+//! Module that contains the [RetryConfig] used to retry failed requests
+
+use crate::error::CurrencyapiError;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::io::ErrorKind;
+use std::time::{Duration, Instant};
+
+/// Configuration of the retry layer enabled with
+/// [CurrencyapiBuilder::retry](crate::api::CurrencyapiBuilder::retry).
+///
+/// Failed attempts are retried with an exponentially growing backoff starting at
+/// `base_backoff` and capped at `max_backoff`. Requests rejected with status `429`
+/// are retried after the delay of the `Retry-After` header instead, if the api sent one.
+///
+/// Only failures that happened before a response was received are retried, i.e. failed
+/// or reset connections and timeouts, besides rate limited requests. Other error
+/// responses of the api and responses that cannot be parsed are not retried. All requests
+/// of the client are idempotent `GET` requests, so a retry never repeats a side effect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Maximum number of attempts including the first request
+    pub max_attempts: u32,
+    /// Backoff before the first retry
+    pub base_backoff: Duration,
+    /// Upper bound of the backoff between two attempts
+    pub max_backoff: Duration,
+    /// Overall time budget for all attempts, defaulting to the client
+    /// [timeout](crate::api::CurrencyapiBuilder::timeout) if unset. An attempt still in
+    /// flight at the deadline is cancelled and no further retry is started if its backoff
+    /// would end after the deadline, the last error is returned instead.
+    pub deadline: Option<Duration>,
+    /// Whether each backoff is shortened by a random amount of up to half, so clients
+    /// failing at the same time do not retry in lockstep
+    pub jitter: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            deadline: None,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Returns the backoff before the given retry, starting at 1 for the first retry.
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.base_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Returns the delay before the given retry, i.e. the backoff with jitter if enabled.
+    fn delay(&self, retry: u32) -> Duration {
+        let backoff = self.backoff(retry);
+        if self.jitter {
+            backoff.mul_f64(1.0 - random_fraction() / 2.0)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Returns a random number in `[0, 1)`, seeded by the random keys of the standard
+/// library's hasher.
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Returns `true` if the connection could not be established, was closed by the peer
+/// or a proxy before the response arrived or was reset while the body was received.
+fn is_connection_error(err: &reqwest::Error) -> bool {
+    if err.is_connect() || err.is_request() {
+        return true;
+    }
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            return matches!(
+                io.kind(),
+                ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+                    | ErrorKind::UnexpectedEof
+            );
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Returns `true` for transient transport errors and rate limited requests that may
+/// succeed on a later attempt.
+fn is_retryable(err: &CurrencyapiError) -> bool {
+    match err {
+        CurrencyapiError::RequestError { source } | CurrencyapiError::ReadBodyError { source } => {
+            is_connection_error(source)
+        }
+        CurrencyapiError::Timeout { .. } | CurrencyapiError::ConnectError { .. } => true,
+        CurrencyapiError::ApiError { status, .. } => *status == 429,
+        _ => false,
+    }
+}
+
+/// Returns the delay requested by the api before retrying after `err`, if any.
+fn requested_delay(err: &CurrencyapiError) -> Option<Duration> {
+    match err {
+        CurrencyapiError::ApiError { retry_after, .. } => *retry_after,
+        _ => None,
+    }
+}
+
+/// Runs `attempt` until it succeeds, fails with a non-retryable error, the maximum
+/// number of attempts is reached or the deadline passes.
+///
+/// An attempt still running at the deadline is cancelled and the error of the previous
+/// attempt is returned, or [CurrencyapiError::Timeout] if there was none.
+pub(crate) async fn retry<T, F, Fut>(
+    config: &RetryConfig,
+    mut attempt: F,
+) -> Result<T, CurrencyapiError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, CurrencyapiError>>,
+{
+    let started_at = Instant::now();
+    let mut attempts = 0;
+    let mut last_err = None;
+    loop {
+        attempts += 1;
+        let result = match config.deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_sub(started_at.elapsed());
+                match tokio::time::timeout(remaining, attempt()).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        return Err(last_err.unwrap_or(CurrencyapiError::Timeout { source: None }))
+                    }
+                }
+            }
+            None => attempt().await,
+        };
+        let err = match result {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        if attempts >= config.max_attempts || !is_retryable(&err) {
+            return Err(err);
+        }
+        let backoff = requested_delay(&err).unwrap_or_else(|| config.delay(attempts));
+        if let Some(deadline) = config.deadline {
+            if started_at.elapsed() + backoff >= deadline {
+                return Err(err);
+            }
+        }
+        last_err = Some(err);
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+#[cfg(test)]
+mod retry_test {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    async fn refused_connection(client: &reqwest::Client) -> Result<(), CurrencyapiError> {
+        client
+            .get("http://127.0.0.1:1/")
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(CurrencyapiError::request)
+    }
+
+    #[test]
+    fn should_grow_backoff_exponentially_up_to_max() {
+        let config = RetryConfig {
+            max_attempts: 10,
+            base_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+            deadline: None,
+            jitter: false,
+        };
+        assert_eq!(config.backoff(1), Duration::from_millis(100));
+        assert_eq!(config.backoff(2), Duration::from_millis(200));
+        assert_eq!(config.backoff(3), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn should_shorten_backoff_by_up_to_half_with_jitter() {
+        let config = RetryConfig {
+            base_backoff: Duration::from_millis(1000),
+            ..RetryConfig::default()
+        };
+        let delays: Vec<Duration> = (0..20).map(|_| config.delay(1)).collect();
+        for delay in &delays {
+            assert!(
+                *delay > Duration::from_millis(500) && *delay <= Duration::from_millis(1000),
+                "delay {:?}",
+                delay
+            );
+        }
+        assert!(delays.iter().any(|delay| *delay != delays[0]));
+    }
+
+    #[tokio::test]
+    async fn should_retry_reset_connections() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(3) {
+                let mut stream = stream.unwrap();
+                let mut buf = [0; 1024];
+                let _ = std::io::Read::read(&mut stream, &mut buf);
+                drop(stream);
+            }
+        });
+        let config = RetryConfig {
+            base_backoff: Duration::from_millis(1),
+            ..RetryConfig::default()
+        };
+        let attempts = AtomicU32::new(0);
+        let res = retry(&config, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            let url = url.clone();
+            async move {
+                reqwest::get(url)
+                    .await
+                    .map(|_| ())
+                    .map_err(CurrencyapiError::request)
+            }
+        })
+        .await;
+        assert!(matches!(res, Err(CurrencyapiError::RequestError { .. })));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn should_stop_retrying_at_the_deadline() {
+        let config = RetryConfig {
+            max_attempts: 20,
+            base_backoff: Duration::from_millis(40),
+            max_backoff: Duration::from_millis(40),
+            deadline: Some(Duration::from_millis(150)),
+            jitter: false,
+        };
+        let client = reqwest::Client::new();
+        let attempts = AtomicU32::new(0);
+        let started_at = Instant::now();
+        let res = retry(&config, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            refused_connection(&client)
+        })
+        .await;
+        assert!(matches!(res, Err(CurrencyapiError::ConnectError { .. })));
+        let attempts = attempts.load(Ordering::SeqCst);
+        assert!(attempts > 1 && attempts < 20, "made {} attempts", attempts);
+        assert!(started_at.elapsed() < Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn should_cancel_slow_attempt_at_the_deadline() {
+        use crate::testing::{MockResponse, MockServer};
+
+        let server = MockServer::start(|_| {
+            MockResponse::json(200, "{}").delayed(Duration::from_millis(2000))
+        });
+        let config = RetryConfig {
+            deadline: Some(Duration::from_millis(200)),
+            ..RetryConfig::default()
+        };
+        let client = reqwest::Client::new();
+        let started_at = Instant::now();
+        let res = retry(&config, || async {
+            client
+                .get(server.url.clone())
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(CurrencyapiError::request)
+        })
+        .await;
+        let elapsed = started_at.elapsed();
+        assert!(matches!(
+            res,
+            Err(CurrencyapiError::Timeout { source: None })
+        ));
+        assert!(elapsed >= Duration::from_millis(200), "took {:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(500), "took {:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn should_return_last_error_when_retry_is_cut_off_at_the_deadline() {
+        let config = RetryConfig {
+            base_backoff: Duration::from_millis(10),
+            deadline: Some(Duration::from_millis(200)),
+            jitter: false,
+            ..RetryConfig::default()
+        };
+        let attempts = AtomicU32::new(0);
+        let started_at = Instant::now();
+        let res: Result<(), _> = retry(&config, || {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt == 0 {
+                    return Err(api_error(429, None));
+                }
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                Ok(())
+            }
+        })
+        .await;
+        let elapsed = started_at.elapsed();
+        assert!(matches!(
+            res,
+            Err(CurrencyapiError::ApiError { status: 429, .. })
+        ));
+        assert!(elapsed < Duration::from_millis(500), "took {:?}", elapsed);
+    }
+
+    fn api_error(status: u16, retry_after: Option<Duration>) -> CurrencyapiError {
+        CurrencyapiError::ApiError {
+            status,
+            message: String::from("error"),
+            errors: Default::default(),
+            retry_after,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_retry_rate_limited_requests_after_requested_delay() {
+        let config = RetryConfig {
+            base_backoff: Duration::from_secs(10),
+            ..RetryConfig::default()
+        };
+        let attempts = AtomicU32::new(0);
+        let started_at = Instant::now();
+        let res = retry(&config, || {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                match attempt {
+                    0 => Err(api_error(429, Some(Duration::from_millis(30)))),
+                    _ => Ok(attempt),
+                }
+            }
+        })
+        .await;
+        assert_eq!(res.unwrap(), 1);
+        let elapsed = started_at.elapsed();
+        assert!(elapsed >= Duration::from_millis(30), "waited {:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(10), "waited {:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn should_not_retry_other_error_responses() {
+        let attempts = AtomicU32::new(0);
+        let res: Result<(), _> = retry(&RetryConfig::default(), || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Err(api_error(401, None)) }
+        })
+        .await;
+        assert!(matches!(
+            res,
+            Err(CurrencyapiError::ApiError { status: 401, .. })
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn should_not_retry_non_transient_errors() {
+        let attempts = AtomicU32::new(0);
+        let res: Result<(), _> = retry(&RetryConfig::default(), || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Err(CurrencyapiError::UrlConstruction) }
+        })
+        .await;
+        assert!(res.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,67 @@
+//! Module that contains the [RoundingMode] used for rounded conversions
+
+/// Rounding strategy applied to converted amounts.
+///
+/// Defaults to [RoundingMode::HalfEven] (banker's rounding) which avoids the
+/// upward bias of [RoundingMode::HalfUp] when summing many rounded values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RoundingMode {
+    /// Round to the nearest value, ties away from zero
+    HalfUp,
+    /// Round to the nearest value, ties to the nearest even digit
+    #[default]
+    HalfEven,
+    /// Round towards negative infinity
+    Floor,
+    /// Round towards positive infinity
+    Ceil,
+}
+
+impl RoundingMode {
+    /// Rounds `value` to `decimals` decimal places using this mode.
+    pub fn round(self, value: f64, decimals: u32) -> f64 {
+        let factor = 10f64.powi(decimals as i32);
+        let scaled = value * factor;
+        let rounded = match self {
+            RoundingMode::HalfUp => scaled.round(),
+            RoundingMode::HalfEven => scaled.round_ties_even(),
+            RoundingMode::Floor => scaled.floor(),
+            RoundingMode::Ceil => scaled.ceil(),
+        };
+        rounded / factor
+    }
+}
+
+#[cfg(test)]
+mod rounding_test {
+    use super::RoundingMode;
+
+    #[test]
+    fn should_default_to_bankers_rounding() {
+        assert_eq!(RoundingMode::default(), RoundingMode::HalfEven);
+    }
+
+    #[test]
+    fn should_round_positive_tie_per_mode() {
+        assert_eq!(RoundingMode::HalfUp.round(0.125, 2), 0.13);
+        assert_eq!(RoundingMode::HalfEven.round(0.125, 2), 0.12);
+        assert_eq!(RoundingMode::Floor.round(0.125, 2), 0.12);
+        assert_eq!(RoundingMode::Ceil.round(0.125, 2), 0.13);
+    }
+
+    #[test]
+    fn should_round_negative_tie_per_mode() {
+        assert_eq!(RoundingMode::HalfUp.round(-0.375, 2), -0.38);
+        assert_eq!(RoundingMode::HalfEven.round(-0.375, 2), -0.38);
+        assert_eq!(RoundingMode::Floor.round(-0.375, 2), -0.38);
+        assert_eq!(RoundingMode::Ceil.round(-0.375, 2), -0.37);
+    }
+
+    #[test]
+    fn should_distinguish_half_up_from_half_even() {
+        assert_eq!(RoundingMode::HalfUp.round(2.5, 0), 3.0);
+        assert_eq!(RoundingMode::HalfEven.round(2.5, 0), 2.0);
+        assert_eq!(RoundingMode::HalfUp.round(3.5, 0), 4.0);
+        assert_eq!(RoundingMode::HalfEven.round(3.5, 0), 4.0);
+    }
+}
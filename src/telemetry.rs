@@ -0,0 +1,105 @@
+//! OpenTelemetry spans following the HTTP client semantic conventions
+
+use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
+use opentelemetry::KeyValue;
+use reqwest::Url;
+
+/// Name of the tracer used for all spans of the crate
+pub(crate) const TRACER_NAME: &str = "currencyapi-rs";
+
+/// Starts a client span for a GET request to the given url.
+pub(crate) fn start_request_span<T: Tracer>(tracer: &T, url: &Url) -> T::Span {
+    let mut attributes = vec![
+        KeyValue::new("http.method", "GET"),
+        KeyValue::new("http.url", url.to_string()),
+    ];
+    if let Some(host) = url.host_str() {
+        attributes.push(KeyValue::new("net.peer.name", host.to_string()));
+    }
+    tracer
+        .span_builder(format!("GET {}", url.path()))
+        .with_kind(SpanKind::Client)
+        .with_attributes(attributes)
+        .start(tracer)
+}
+
+/// Records the outcome of the request on the span and ends it.
+pub(crate) fn end_request_span<S: Span>(
+    mut span: S,
+    result: Result<reqwest::StatusCode, &reqwest::Error>,
+) {
+    match result {
+        Ok(status) => {
+            span.set_attribute(KeyValue::new(
+                "http.status_code",
+                i64::from(status.as_u16()),
+            ));
+            if status.is_client_error() || status.is_server_error() {
+                span.set_status(Status::error(status.to_string()));
+            } else {
+                span.set_status(Status::Ok);
+            }
+        }
+        Err(err) => {
+            if let Some(status) = err.status() {
+                span.set_attribute(KeyValue::new(
+                    "http.status_code",
+                    i64::from(status.as_u16()),
+                ));
+            }
+            span.set_status(Status::error(err.to_string()));
+        }
+    }
+    span.end();
+}
+
+#[cfg(test)]
+mod telemetry_test {
+    use super::*;
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry::Value;
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+
+    fn attribute(attributes: &[KeyValue], key: &str) -> Option<Value> {
+        attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.clone())
+    }
+
+    #[test]
+    fn should_produce_span_with_semantic_attributes() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let tracer = provider.tracer(TRACER_NAME);
+        let url = Url::parse("https://api.currencyapi.com/v3/latest?base_currency=USD").unwrap();
+
+        let span = start_request_span(&tracer, &url);
+        end_request_span(span, Ok(reqwest::StatusCode::OK));
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        let span = &spans[0];
+        assert_eq!(span.name, "GET /v3/latest");
+        assert_eq!(span.span_kind, SpanKind::Client);
+        assert_eq!(span.status, Status::Ok);
+        assert_eq!(
+            attribute(&span.attributes, "http.method"),
+            Some("GET".into())
+        );
+        assert_eq!(
+            attribute(&span.attributes, "http.url"),
+            Some(url.to_string().into())
+        );
+        assert_eq!(
+            attribute(&span.attributes, "net.peer.name"),
+            Some("api.currencyapi.com".to_string().into())
+        );
+        assert_eq!(
+            attribute(&span.attributes, "http.status_code"),
+            Some(200i64.into())
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Minimal http server answering requests with canned responses in tests
+
+use reqwest::Url;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Canned response of the [MockServer]
+pub(crate) struct MockResponse {
+    pub(crate) status: u16,
+    pub(crate) body: String,
+    pub(crate) delay: Duration,
+    pub(crate) headers: Vec<(String, String)>,
+}
+
+impl MockResponse {
+    pub(crate) fn json(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            body: String::from(body),
+            delay: Duration::ZERO,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Adds a header to the response.
+    pub(crate) fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((String::from(name), String::from(value)));
+        self
+    }
+
+    /// Delays sending the response by `delay`.
+    pub(crate) fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// Answers every received request with the response returned by the responder
+/// for the request target (path and query). Connections are handled concurrently.
+pub(crate) struct MockServer {
+    pub(crate) url: Url,
+}
+
+impl MockServer {
+    pub(crate) fn start<F>(responder: F) -> Self
+    where
+        F: Fn(&str) -> MockResponse + Send + Sync + 'static,
+    {
+        Self::start_with_head(move |target, _| responder(target))
+    }
+
+    /// Starts a server like [MockServer::start] with a responder that also receives the
+    /// request head, i.e. the request line and headers.
+    pub(crate) fn start_with_head<F>(responder: F) -> Self
+    where
+        F: Fn(&str, &str) -> MockResponse + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let responder = Arc::new(responder);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let responder = Arc::clone(&responder);
+                thread::spawn(move || {
+                    let mut head = String::new();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    loop {
+                        let mut line = String::new();
+                        if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                            break;
+                        }
+                        head.push_str(&line);
+                    }
+                    let target = head.split_whitespace().nth(1).unwrap_or("/").to_string();
+                    let response = responder(&target, &head);
+                    thread::sleep(response.delay);
+                    let headers: String = response
+                        .headers
+                        .iter()
+                        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+                        .collect();
+                    let raw = format!(
+                        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+                        response.status,
+                        response.body.len(),
+                        headers,
+                        response.body
+                    );
+                    let _ = stream.write_all(raw.as_bytes());
+                });
+            }
+        });
+        Self { url }
+    }
+
+    /// Returns the url of the given path on the server.
+    pub(crate) fn path(&self, path: &str) -> Url {
+        self.url.join(path).unwrap()
+    }
+}
+
+/// Subscriber recording the fields of all spans and events as `name=value` strings
+#[cfg(feature = "tracing")]
+#[derive(Clone, Default)]
+pub(crate) struct FieldRecorder {
+    fields: Arc<std::sync::Mutex<Vec<String>>>,
+    spans: Arc<std::sync::Mutex<Vec<&'static tracing::Metadata<'static>>>>,
+    entered: Arc<std::sync::Mutex<Vec<tracing::span::Id>>>,
+}
+
+#[cfg(feature = "tracing")]
+impl FieldRecorder {
+    /// Returns all fields recorded so far.
+    pub(crate) fn fields(&self) -> Vec<String> {
+        self.fields.lock().unwrap().clone()
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl tracing::field::Visit for &FieldRecorder {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        let field = format!("{}={}", field.name(), value);
+        self.fields.lock().unwrap().push(field);
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        let field = format!("{}={:?}", field.name(), value);
+        self.fields.lock().unwrap().push(field);
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for FieldRecorder {
+    fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        span.record(&mut &*self);
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(span.metadata());
+        tracing::span::Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+        values.record(&mut &*self);
+    }
+
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        event.record(&mut &*self);
+    }
+
+    fn enter(&self, span: &tracing::span::Id) {
+        self.entered.lock().unwrap().push(span.clone());
+    }
+
+    fn exit(&self, _: &tracing::span::Id) {
+        self.entered.lock().unwrap().pop();
+    }
+
+    fn current_span(&self) -> tracing_core::span::Current {
+        let entered = self.entered.lock().unwrap();
+        match entered.last() {
+            Some(id) => {
+                let metadata = self.spans.lock().unwrap()[id.into_u64() as usize - 1];
+                tracing_core::span::Current::new(id.clone(), metadata)
+            }
+            None => tracing_core::span::Current::none(),
+        }
+    }
+}
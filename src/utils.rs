@@ -4,31 +4,81 @@ pub mod baseline {
     use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
     use reqwest::{Client, Url};
 
-    const BASE_URL: &str = "https://api.currencyapi.com/v3/";
+    /// Origin of the currencyapi the version path segment is appended to
+    const API_ORIGIN: &str = "https://api.currencyapi.com/";
+
+    /// Version path segment of the api used unless configured otherwise
+    pub(crate) const DEFAULT_API_VERSION: &str = "v3";
+
+    /// User agent sent with each request unless configured otherwise
+    pub(crate) const DEFAULT_USER_AGENT: &str =
+        concat!("currencyapi-rs/", env!("CARGO_PKG_VERSION"));
 
     pub fn construct_client(
         user_agent: Option<&str>,
-        _: &api::Settings,
+        settings: &api::Settings,
     ) -> Result<Client, CurrencyapiError> {
-        let mut headers = HeaderMap::new();
-        let content_type = HeaderValue::from_str("application/json")?;
-        headers.insert(CONTENT_TYPE, content_type);
-        let agent = user_agent.map_or_else(
-            || format!("{}/{}", "", ""),
-            String::from,
-        );
-        let client = Client::builder()
-            .user_agent(agent)
-            .default_headers(headers)
+        let headers = default_headers(settings.api_key())?;
+        let agent = user_agent.unwrap_or(DEFAULT_USER_AGENT);
+        let mut builder = Client::builder().user_agent(agent).default_headers(headers);
+        if let Some(timeout) = settings.timeout() {
+            builder = builder.timeout(timeout);
+        }
+        let client = builder
             .build()
             .map_err(|err| CurrencyapiError::ClientConstruction { source: err })?;
         Ok(client)
     }
 
+    /// Constructs the http client of the [crate::blocking::Currencyapi].
+    #[cfg(feature = "blocking")]
+    pub fn construct_blocking_client(
+        api_key: &str,
+    ) -> Result<reqwest::blocking::Client, CurrencyapiError> {
+        reqwest::blocking::Client::builder()
+            .user_agent(DEFAULT_USER_AGENT)
+            .default_headers(default_headers(api_key)?)
+            .build()
+            .map_err(|err| CurrencyapiError::ClientConstruction { source: err })
+    }
+
+    /// Headers sent with every request, including the api key as sensitive header.
+    pub(crate) fn default_headers(api_key: &str) -> Result<HeaderMap, CurrencyapiError> {
+        let mut headers = HeaderMap::new();
+        let content_type = HeaderValue::from_str("application/json")?;
+        headers.insert(CONTENT_TYPE, content_type);
+        let mut api_key = HeaderValue::from_str(api_key)?;
+        api_key.set_sensitive(true);
+        headers.insert("apikey", api_key);
+        Ok(headers)
+    }
+
+    /// Parses the url the endpoint paths are appended to, accepting only http and https
+    /// urls that can have a path.
+    pub fn parse_base_url(base_url: &str) -> Result<Url, CurrencyapiError> {
+        let url = Url::parse(base_url).map_err(|_| CurrencyapiError::UrlConstruction)?;
+        if url.cannot_be_a_base() || !matches!(url.scheme(), "http" | "https") {
+            return Err(CurrencyapiError::UrlConstruction);
+        }
+        Ok(url)
+    }
+
+    /// Returns the base url of the currencyapi for the version path segment `version`,
+    /// e.g. `https://api.currencyapi.com/v4/` for `v4`.
+    pub fn versioned_base_url(version: &str) -> Result<Url, CurrencyapiError> {
+        if version.is_empty() || version.contains(['/', '?', '#']) {
+            return Err(CurrencyapiError::InvalidConfiguration {
+                reason: format!("invalid api version '{}'", version),
+            });
+        }
+        parse_base_url(&format!("{}{}/", API_ORIGIN, version))
+    }
+
     pub fn construct_base_url(
+        base_url: &Url,
         with_path: Option<&str>,
     ) -> Result<Url, CurrencyapiError> {
-        let mut url = Url::parse(BASE_URL).map_err(|_| CurrencyapiError::UrlConstruction)?;
+        let mut url = base_url.clone();
         if let Some(path) = with_path {
             let trimmed_path = path.trim_start_matches('/');
             let new_path = format!("{}/{}", url.path().trim_end_matches('/'), trimmed_path);
@@ -44,14 +94,341 @@ mod baseline_test {
 
     #[test]
     fn should_create_base_url_with_api_key() {
-        let base_url = construct_base_url(None).unwrap();
+        let base_url =
+            construct_base_url(&versioned_base_url(DEFAULT_API_VERSION).unwrap(), None).unwrap();
         assert_eq!(base_url.path(), "/v3/");
+        assert_eq!(base_url.query(), None);
     }
 
     #[test]
     fn should_create_base_url_with_api_key_and_path() {
-        let base_url = construct_base_url(Some("/test/path")).unwrap();
+        let base_url = versioned_base_url(DEFAULT_API_VERSION).unwrap();
+        let base_url = construct_base_url(&base_url, Some("/test/path")).unwrap();
         assert_eq!(base_url.path(), "/v3/test/path");
+        assert_eq!(base_url.query(), None);
     }
 
+    #[test]
+    fn should_append_path_to_custom_base_url() {
+        let base_url = parse_base_url("http://127.0.0.1:8080").unwrap();
+        let url = construct_base_url(&base_url, Some("latest")).unwrap();
+        assert_eq!(url.as_str(), "http://127.0.0.1:8080/latest");
+        let base_url = parse_base_url("https://proxy.example.com/currencyapi/v3/").unwrap();
+        let url = construct_base_url(&base_url, Some("latest")).unwrap();
+        assert_eq!(url.path(), "/currencyapi/v3/latest");
+        assert_eq!(
+            versioned_base_url("v4").unwrap().as_str(),
+            "https://api.currencyapi.com/v4/"
+        );
+        assert!(versioned_base_url("").is_err());
+        assert!(versioned_base_url("v4/latest").is_err());
+        for invalid in [
+            "not a url",
+            "mailto:rates@example.com",
+            "ftp://example.com/",
+        ] {
+            assert!(parse_base_url(invalid).is_err());
+        }
+    }
+
+}
+
+pub mod numbers {
+    use crate::error::CurrencyapiError;
+
+    /// Splits a json number literal into its sign, significant digits and the decimal
+    /// exponent of the last digit so that different notations of the same value compare equal.
+    fn normalize(literal: &str) -> Option<(bool, String, i64)> {
+        let (negative, unsigned) = match literal.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, literal),
+        };
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(idx) => (&unsigned[..idx], unsigned[idx + 1..].parse::<i64>().ok()?),
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let digits = format!("{}{}", integer, fraction);
+        let mut exponent = exponent - fraction.len() as i64;
+        let trimmed = digits.trim_end_matches('0');
+        exponent += (digits.len() - trimmed.len()) as i64;
+        let trimmed = trimmed.trim_start_matches('0');
+        if trimmed.is_empty() {
+            return Some((false, String::from("0"), 0));
+        }
+        Some((negative, String::from(trimmed), exponent))
+    }
+
+    /// Returns `true` if the json number literal is represented exactly by its nearest `f64`.
+    fn is_exact(literal: &str) -> bool {
+        let Ok(value) = literal.parse::<f64>() else {
+            return false;
+        };
+        value.is_finite() && normalize(literal) == normalize(&format!("{:e}", value))
+    }
+
+    /// Checks every number in the json body and returns an error for the first one
+    /// that would lose precision when deserialized into an `f64`.
+    pub fn ensure_lossless(body: &str) -> Result<(), CurrencyapiError> {
+        match number_literals(body).find(|literal| !is_exact(&body[literal.clone()])) {
+            Some(literal) => Err(CurrencyapiError::PrecisionLoss {
+                value: String::from(&body[literal]),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Rewrites every number in the json body as a string holding the same literal, so
+    /// that [rust_decimal::Decimal] values are parsed from their exact digits instead of
+    /// the nearest `f64`. Returns an error for the first number a `Decimal` cannot hold.
+    #[cfg(feature = "decimal")]
+    pub fn quote_exact_decimals(body: &str) -> Result<String, CurrencyapiError> {
+        use rust_decimal::Decimal;
+
+        let mut quoted = String::with_capacity(body.len() + 16);
+        let mut copied = 0;
+        for literal in number_literals(body) {
+            let value = &body[literal.clone()];
+            if Decimal::from_str_exact(value)
+                .or_else(|_| Decimal::from_scientific(value))
+                .is_err()
+            {
+                return Err(CurrencyapiError::PrecisionLoss {
+                    value: String::from(value),
+                });
+            }
+            quoted.push_str(&body[copied..literal.start]);
+            quoted.push('"');
+            quoted.push_str(value);
+            quoted.push('"');
+            copied = literal.end;
+        }
+        quoted.push_str(&body[copied..]);
+        Ok(quoted)
+    }
+
+    /// Returns the byte ranges of all number literals in the json body, skipping strings.
+    fn number_literals(body: &str) -> impl Iterator<Item = std::ops::Range<usize>> + '_ {
+        let mut chars = body.char_indices().peekable();
+        std::iter::from_fn(move || {
+            while let Some((start, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        let mut escaped = false;
+                        for (_, c) in chars.by_ref() {
+                            match c {
+                                '\\' if !escaped => escaped = true,
+                                '"' if !escaped => break,
+                                _ => escaped = false,
+                            }
+                        }
+                    }
+                    '-' | '0'..='9' => {
+                        let mut end = start + c.len_utf8();
+                        while let Some(&(idx, c)) = chars.peek() {
+                            if !matches!(c, '0'..='9' | '.' | 'e' | 'E' | '+' | '-') {
+                                break;
+                            }
+                            end = idx + c.len_utf8();
+                            chars.next();
+                        }
+                        return Some(start..end);
+                    }
+                    _ => {}
+                }
+            }
+            None
+        })
+    }
+}
+
+#[cfg(test)]
+mod numbers_test {
+    use super::numbers::*;
+
+    #[test]
+    fn should_accept_numbers_representable_as_f64() {
+        let body = r#"{"data":{"EUR":{"code":"EUR","value":0.92},"JPY":{"code":"JPY","value":1.5e2}},"n":-10}"#;
+        assert!(ensure_lossless(body).is_ok());
+    }
+
+    #[test]
+    fn should_reject_high_precision_numbers() {
+        let body = r#"{"data":{"BTC":{"code":"BTC","value":0.0000159876543210987654321}}}"#;
+        let err = ensure_lossless(body).unwrap_err();
+        assert!(err.to_string().contains("0.0000159876543210987654321"));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn should_quote_numbers_with_their_exact_digits() {
+        let body =
+            r#"{"data":{"BTC":{"code":"BTC","value":0.0000159876543210987654321}},"s":"1.5"}"#;
+        assert_eq!(
+            quote_exact_decimals(body).unwrap(),
+            r#"{"data":{"BTC":{"code":"BTC","value":"0.0000159876543210987654321"}},"s":"1.5"}"#
+        );
+        let body = r#"{"value":0.00000000000000000000000000001}"#;
+        assert!(quote_exact_decimals(body).is_err());
+    }
+
+    #[test]
+    fn should_ignore_numbers_inside_strings() {
+        let body = r#"{"meta":{"last_updated_at":"2023-06-23T10:15:59.123456789012345678Z"},"x":"1.00000000000000000001\""}"#;
+        assert!(ensure_lossless(body).is_ok());
+    }
+}
+
+pub mod duplicates {
+    use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+    use std::collections::HashSet;
+    use std::fmt;
+
+    /// Returns the first currency code that appears more than once in the `data` map
+    /// of a response body.
+    pub fn find_duplicate_code(body: &str) -> Result<Option<String>, serde_json::Error> {
+        let mut deserializer = serde_json::Deserializer::from_str(body);
+        let duplicate = deserializer.deserialize_map(ResponseVisitor)?;
+        deserializer.end()?;
+        Ok(duplicate)
+    }
+
+    /// Visits the top level of a response and checks the keys of its `data` map.
+    struct ResponseVisitor;
+
+    impl<'de> Visitor<'de> for ResponseVisitor {
+        type Value = Option<String>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a response object")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut duplicate = None;
+            while let Some(key) = map.next_key::<String>()? {
+                if key == "data" && duplicate.is_none() {
+                    duplicate = map.next_value_seed(DataKeys)?;
+                } else {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+            Ok(duplicate)
+        }
+    }
+
+    /// Collects the keys of the `data` map and stops at the first duplicate.
+    struct DataKeys;
+
+    impl<'de> DeserializeSeed<'de> for DataKeys {
+        type Value = Option<String>;
+
+        fn deserialize<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for DataKeys {
+        type Value = Option<String>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map of currencies")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut seen = HashSet::new();
+            let mut duplicate = None;
+            while let Some(code) = map.next_key::<String>()? {
+                map.next_value::<IgnoredAny>()?;
+                if duplicate.is_none() && !seen.insert(code.clone()) {
+                    duplicate = Some(code);
+                }
+            }
+            Ok(duplicate)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            while seq.next_element::<IgnoredAny>()?.is_some() {}
+            Ok(None)
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod duplicates_test {
+    use super::duplicates::*;
+
+    #[test]
+    fn should_find_duplicate_currency_code() {
+        let body = r#"{"data":{"BTC":{"code":"BTC","value":0.1},"EUR":{"code":"EUR","value":0.9},"BTC":{"code":"BTC","value":0.2}},"meta":null}"#;
+        assert_eq!(
+            find_duplicate_code(body).unwrap(),
+            Some(String::from("BTC"))
+        );
+    }
+
+    #[test]
+    fn should_accept_unique_currency_codes() {
+        let body = r#"{"meta":{"last_updated_at":"2024-01-01T00:00:00Z"},"data":{"BTC":{"code":"BTC","value":0.1},"EUR":{"code":"EUR","value":0.9}}}"#;
+        assert_eq!(find_duplicate_code(body).unwrap(), None);
+    }
+}
+
+pub mod json {
+    use serde::de::DeserializeOwned;
+
+    /// Error of the JSON backend selected by the `simd-json` feature.
+    #[cfg(not(feature = "simd-json"))]
+    pub type Error = serde_json::Error;
+
+    /// Error of the JSON backend selected by the `simd-json` feature.
+    #[cfg(feature = "simd-json")]
+    pub type Error = simd_json::Error;
+
+    /// Deserializes a response body with `serde_json`.
+    #[cfg(not(feature = "simd-json"))]
+    pub fn from_str<T: DeserializeOwned>(body: &str) -> Result<T, Error> {
+        serde_json::from_str(body)
+    }
+
+    /// Deserializes a response body with `simd-json`, which parses in place
+    /// and therefore works on a copy of the body.
+    #[cfg(feature = "simd-json")]
+    pub fn from_str<T: DeserializeOwned>(body: &str) -> Result<T, Error> {
+        let mut bytes = body.as_bytes().to_vec();
+        simd_json::serde::from_slice(&mut bytes)
+    }
+}
+
+#[cfg(test)]
+mod json_test {
+    use super::json::*;
+    use crate::models::{DetailsResponse, LatestResponse};
+
+    const BODY: &str = r#"{"meta":{"last_updated_at":"2024-01-01T23:59:59Z"},"data":{"BTC":{"code":"BTC","value":0.0000234567},"EUR":{"code":"EUR","value":0.9134},"JPY":{"code":"JPY","value":148.123456789}}}"#;
+
+    #[test]
+    fn should_match_serde_json_for_typed_models() {
+        let parsed: LatestResponse = from_str(BODY).unwrap();
+        let expected: LatestResponse = serde_json::from_str(BODY).unwrap();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn should_match_serde_json_for_untyped_models() {
+        let parsed: DetailsResponse = from_str(BODY).unwrap();
+        let expected: DetailsResponse = serde_json::from_str(BODY).unwrap();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn should_reject_malformed_body() {
+        assert!(from_str::<LatestResponse>(r#"{"data":"#).is_err());
+    }
 }